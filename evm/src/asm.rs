@@ -0,0 +1,260 @@
+// 汇编器：把助记符程序转换为字节码
+//
+// 源码格式：每行一条指令，`//` 或 `;` 之后为注释，空行忽略
+//   PUSH1 0x0a      // PUSHn 后跟立即数，支持十六进制（0x 前缀）和十进制
+//   PUSH1 10
+//   ADD
+
+use crate::*;
+use std::fmt;
+
+// 不带立即数、名称固定的指令
+const MNEMONICS: &[(&str, u8)] = &[
+    ("STOP", STOP),
+    ("ADD", ADD),
+    ("MUL", MUL),
+    ("SUB", SUB),
+    ("DIV", DIV),
+    ("SDIV", SDIV),
+    ("MOD", MOD),
+    ("SMOD", SMOD),
+    ("LT", LT),
+    ("GT", GT),
+    ("EQ", EQ),
+    ("AND", AND),
+    ("OR", OR),
+    ("NOT", NOT),
+    ("SHA3", SHA3),
+    ("KECCAK256", SHA3),
+    ("ADDRESS", ADDRESS),
+    ("BALANCE", BALANCE),
+    ("CALLER", CALLER),
+    ("CALLVALUE", CALLVALUE),
+    ("CALLDATALOAD", CALLDATALOAD),
+    ("CALLDATASIZE", CALLDATASIZE),
+    ("CALLDATACOPY", CALLDATACOPY),
+    ("GASPRICE", GASPRICE),
+    ("RETURNDATASIZE", RETURNDATASIZE),
+    ("RETURNDATACOPY", RETURNDATACOPY),
+    ("EXTCODESIZE", EXTCODESIZE),
+    ("EXTCODECOPY", EXTCODECOPY),
+    ("EXTCODEHASH", EXTCODEHASH),
+    ("BLOCKHASH", BLOCKHASH),
+    ("COINBASE", COINBASE),
+    ("TIMESTAMP", TIMESTAMP),
+    ("NUMBER", NUMBER),
+    ("PREVRANDAO", PREVRANDAO),
+    ("GASLIMIT", GASLIMIT),
+    ("CHAINID", CHAINID),
+    ("SELFBALANCE", SELFBALANCE),
+    ("BASEFEE", BASEFEE),
+    ("BLOBBASEFEE", BLOBBASEFEE),
+    ("POP", POP),
+    ("MLOAD", MLOAD),
+    ("MSTORE", MSTORE),
+    ("MSTORE8", MSTORE8),
+    ("SLOAD", SLOAD),
+    ("SSTORE", SSTORE),
+    ("JUMP", JUMP),
+    ("JUMPI", JUMPI),
+    ("PC", PC),
+    ("MSIZE", MSIZE),
+    ("JUMPDEST", JUMPDEST),
+    ("PUSH0", PUSH0),
+    ("CREATE", CREATE),
+    ("CALL", CALL),
+    ("STATICCALL", STATICCALL),
+    ("RETURN", RETURN),
+    ("REVERT", REVERT),
+    ("INVALID", INVALID),
+];
+
+/// 汇编失败的原因，`line` 为出错的行号（从 1 开始）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 行：{}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+// 解析 PUSHn/DUPn/SWAPn/LOGn 这类带编号的指令，返回编号
+fn numbered(name: &str, prefix: &str, min: u8, max: u8) -> Option<u8> {
+    let n: u8 = name.strip_prefix(prefix)?.parse().ok()?;
+    (min..=max).contains(&n).then_some(n)
+}
+
+// 解析 PUSHn 的立即数，编码为 size 字节的大端序
+fn parse_immediate(text: &str, size: usize) -> Result<Vec<u8>, String> {
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(digits) => U256::from_str_radix(digits, 16).ok(),
+        None => U256::from_dec_str(text).ok(),
+    };
+    let value = parsed.ok_or_else(|| format!("无法解析立即数 `{}`", text))?;
+    if value.bits() > size * 8 {
+        return Err(format!("立即数 `{}` 超出 PUSH{} 的 {} 字节", text, size, size));
+    }
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    Ok(buf[32 - size..].to_vec())
+}
+
+/// 将助记符源码汇编为字节码
+pub fn assemble(source: &str) -> Result<Vec<u8>, AsmError> {
+    let mut code = Vec::new();
+    for (index, raw_line) in source.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: String| AsmError { line, message };
+
+        let text = raw_line.split("//").next().unwrap_or("");
+        let text = text.split(';').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut parts = text.split_whitespace();
+        let name = parts.next().unwrap().to_ascii_uppercase();
+        let operand = parts.next();
+        if parts.next().is_some() {
+            return Err(error(format!("`{}` 的操作数过多", name)));
+        }
+
+        if let Some(size) = numbered(&name, "PUSH", 1, 32) {
+            let operand = operand.ok_or_else(|| error(format!("{} 缺少立即数", name)))?;
+            code.push(PUSH1 + size - 1);
+            code.extend(parse_immediate(operand, size as usize).map_err(error)?);
+            continue;
+        }
+
+        let op = if let Some(n) = numbered(&name, "DUP", 1, 16) {
+            DUP1 + n - 1
+        } else if let Some(n) = numbered(&name, "SWAP", 1, 16) {
+            SWAP1 + n - 1
+        } else if let Some(n) = numbered(&name, "LOG", 0, 4) {
+            LOG0 + n
+        } else {
+            MNEMONICS
+                .iter()
+                .find(|(mnemonic, _)| *mnemonic == name)
+                .map(|(_, op)| *op)
+                .ok_or_else(|| error(format!("未知指令 `{}`", name)))?
+        };
+        if operand.is_some() {
+            return Err(error(format!("{} 不接受操作数", name)));
+        }
+        code.push(op);
+    }
+    Ok(code)
+}
+
+/// 反汇编得到的一条指令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub pc: usize,
+    pub opcode: u8,
+    // PUSHn 的立即数（代码末尾不足 n 字节时按实际剩余截断）
+    pub immediate: Vec<u8>,
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> String {
+        opcode_name(self.opcode)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mnemonic())?;
+        if !self.immediate.is_empty() {
+            write!(f, " 0x{}", hex::encode(&self.immediate))?;
+        }
+        Ok(())
+    }
+}
+
+/// 指令助记符，未知 opcode 显示为 `UNKNOWN(0x..)`
+pub fn opcode_name(op: u8) -> String {
+    match op {
+        PUSH1..=PUSH32 => format!("PUSH{}", op - PUSH1 + 1),
+        DUP1..=DUP16 => format!("DUP{}", op - DUP1 + 1),
+        SWAP1..=SWAP16 => format!("SWAP{}", op - SWAP1 + 1),
+        LOG0..=LOG4 => format!("LOG{}", op - LOG0),
+        _ => MNEMONICS
+            .iter()
+            .find(|(_, code)| *code == op)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| format!("UNKNOWN(0x{:02x})", op)),
+    }
+}
+
+/// 将字节码反汇编为指令列表，PUSH 的立即数不会被当成指令
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        let size = if (PUSH1..=PUSH32).contains(&opcode) { (opcode - PUSH1 + 1) as usize } else { 0 };
+        let end = (pc + 1 + size).min(code.len());
+        instructions.push(Instruction { pc, opcode, immediate: code[pc + 1..end].to_vec() });
+        pc += 1 + size;
+    }
+    instructions
+}
+
+/// 指令从堆栈弹出和压入的元素个数 `(pops, pushes)`，未知 opcode 返回 None
+pub fn stack_io(op: u8) -> Option<(usize, usize)> {
+    let io = match op {
+        STOP | JUMPDEST | INVALID => (0, 0),
+        PUSH0 | PUSH1..=PUSH32 | PC | MSIZE | ADDRESS | COINBASE | TIMESTAMP | NUMBER | PREVRANDAO | GASLIMIT
+        | CHAINID | SELFBALANCE | BASEFEE | BLOBBASEFEE | RETURNDATASIZE | CALLER | CALLVALUE | CALLDATASIZE
+        | GASPRICE => (0, 1),
+        POP | JUMP => (1, 0),
+        NOT | MLOAD | SLOAD | BLOCKHASH | BALANCE | EXTCODESIZE | EXTCODEHASH | CALLDATALOAD => (1, 1),
+        ADD | SUB | MUL | DIV | SDIV | MOD | SMOD | LT | GT | EQ | AND | OR | SHA3 => (2, 1),
+        MSTORE | MSTORE8 | SSTORE | JUMPI | RETURN | REVERT => (2, 0),
+        RETURNDATACOPY | CALLDATACOPY => (3, 0),
+        EXTCODECOPY => (4, 0),
+        LOG0..=LOG4 => ((op - LOG0) as usize + 2, 0),
+        DUP1..=DUP16 => {
+            let n = (op - DUP1 + 1) as usize;
+            (n, n + 1)
+        }
+        SWAP1..=SWAP16 => {
+            let n = (op - SWAP1 + 2) as usize;
+            (n, n)
+        }
+        CREATE => (3, 1),
+        CALL => (7, 1),
+        STATICCALL => (6, 1),
+        _ => return None,
+    };
+    Some(io)
+}
+
+/// 指令对堆栈高度的净影响，例如 MSTORE 为 -2、PUSH1 为 +1
+pub fn stack_effect(op: u8) -> Option<i32> {
+    stack_io(op).map(|(pops, pushes)| pushes as i32 - pops as i32)
+}
+
+/// 带注释的反汇编：每条指令一行，列出 pc、助记符（含 PUSH 立即数）、固定 gas 和堆栈净影响，
+/// 例如 `   7: MSTORE           gas=3     stack=-2`
+pub fn annotate(code: &[u8]) -> String {
+    let mut out = String::new();
+    for ins in disassemble(code) {
+        let effect = stack_effect(ins.opcode).map_or("?".to_string(), |effect| format!("{:+}", effect));
+        out.push_str(&format!(
+            "{:>4}: {:<16} gas={:<5} stack={}\n",
+            ins.pc,
+            ins.to_string(),
+            gas::static_gas(ins.opcode),
+            effect
+        ));
+    }
+    out
+}
@@ -0,0 +1,131 @@
+use crate::EvmError;
+use ethereum_types::{Address, H256};
+use primitive_types::U256;
+use std::str::FromStr;
+
+/// 区块环境：所有区块信息类指令（COINBASE、TIMESTAMP、NUMBER 等）统一从这里读取
+/// 通过 `with_*` 方法链式构造，未设置的字段使用默认值
+#[derive(Debug, Clone)]
+pub struct BlockEnv {
+    pub blockhash: H256,
+    pub coinbase: Address,
+    pub timestamp: U256,
+    pub number: U256,
+    pub prevrandao: H256,
+    pub gaslimit: U256,
+    pub chainid: U256,
+    pub basefee: U256,
+    pub blobbasefee: U256,
+}
+
+impl Default for BlockEnv {
+    fn default() -> Self {
+        let blockhash = H256::from_slice(&hex::decode("7527123fc877fe753b3122dc592671b4902ebf2b325dd2c7224a43c0cbeee3ca").unwrap());
+        let coinbase = Address::from_slice(&hex::decode("388C818CA8B9251b393131C08a736A67ccB19297").unwrap());
+        let prevrandao = H256::from_slice(&hex::decode("ce124dee50136f3f93f19667fb4198c6b94eecbacfa300469e5280012757be94").unwrap());
+        Self {
+            blockhash,
+            coinbase,
+            timestamp: U256::from(1625900000),
+            number: U256::from(17871709),
+            prevrandao,
+            gaslimit: U256::from(30),
+            chainid: U256::from(1),
+            basefee: U256::from(30),
+            blobbasefee: U256::from(1),
+        }
+    }
+}
+
+impl BlockEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_blockhash(mut self, blockhash: H256) -> Self {
+        self.blockhash = blockhash;
+        self
+    }
+
+    pub fn with_coinbase(mut self, coinbase: Address) -> Self {
+        self.coinbase = coinbase;
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: U256) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_number(mut self, number: U256) -> Self {
+        self.number = number;
+        self
+    }
+
+    pub fn with_prevrandao(mut self, prevrandao: H256) -> Self {
+        self.prevrandao = prevrandao;
+        self
+    }
+
+    pub fn with_gaslimit(mut self, gaslimit: U256) -> Self {
+        self.gaslimit = gaslimit;
+        self
+    }
+
+    pub fn with_chainid(mut self, chainid: U256) -> Self {
+        self.chainid = chainid;
+        self
+    }
+
+    pub fn with_basefee(mut self, basefee: U256) -> Self {
+        self.basefee = basefee;
+        self
+    }
+
+    pub fn with_blobbasefee(mut self, blobbasefee: U256) -> Self {
+        self.blobbasefee = blobbasefee;
+        self
+    }
+}
+
+// 解析十进制或 0x 开头的十六进制整数
+fn parse_u256(text: &str) -> Option<U256> {
+    match text.strip_prefix("0x") {
+        Some(digits) => U256::from_str_radix(digits, 16).ok(),
+        None => U256::from_dec_str(text).ok(),
+    }
+}
+
+impl BlockEnv {
+    /// 从环境变量覆盖区块字段，未设置的字段保持默认值：
+    /// EVM_TIMESTAMP、EVM_NUMBER、EVM_BASEFEE、EVM_CHAINID、EVM_COINBASE
+    pub fn from_env() -> Result<Self, EvmError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// 按变量名查询取值并解析，`from_env` 传入的是 `std::env::var`，便于单独测试解析逻辑
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, EvmError> {
+        let mut env = Self::default();
+        let invalid = |name: &str, value: &str| EvmError::InvalidEnvVar {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        let numbers: [(&str, &mut U256); 4] = [
+            ("EVM_TIMESTAMP", &mut env.timestamp),
+            ("EVM_NUMBER", &mut env.number),
+            ("EVM_BASEFEE", &mut env.basefee),
+            ("EVM_CHAINID", &mut env.chainid),
+        ];
+        for (name, field) in numbers {
+            if let Some(value) = lookup(name) {
+                *field = parse_u256(value.trim()).ok_or_else(|| invalid(name, &value))?;
+            }
+        }
+
+        if let Some(value) = lookup("EVM_COINBASE") {
+            env.coinbase = Address::from_str(value.trim()).map_err(|_| invalid("EVM_COINBASE", &value))?;
+        }
+        Ok(env)
+    }
+}
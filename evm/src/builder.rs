@@ -0,0 +1,35 @@
+// 字节码片段构造：用函数拼出常见的指令序列，不必手写 opcode
+//
+//   let mut code = mstore_word(0, U256::from(42));
+//   code.extend(return_range(0, 32));   // 返回 42
+
+use crate::*;
+
+/// 把 value 压入堆栈的最短指令：0 使用 PUSH0，其余使用能装下 value 的最小 PUSHn（大端序）
+pub fn push_u256(value: U256) -> Vec<u8> {
+    if value.is_zero() {
+        return vec![PUSH0];
+    }
+    let size = value.bits().div_ceil(8);
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    let mut code = vec![PUSH1 + size as u8 - 1];
+    code.extend_from_slice(&buf[32 - size..]);
+    code
+}
+
+/// 把 value 写入内存 offset 处的 32 字节
+pub fn mstore_word(offset: usize, value: U256) -> Vec<u8> {
+    let mut code = push_u256(value);
+    code.extend(push_u256(U256::from(offset)));
+    code.push(MSTORE);
+    code
+}
+
+/// 以内存 [offset, offset + len) 为返回数据结束执行
+pub fn return_range(offset: usize, len: usize) -> Vec<u8> {
+    let mut code = push_u256(U256::from(len));
+    code.extend(push_u256(U256::from(offset)));
+    code.push(RETURN);
+    code
+}
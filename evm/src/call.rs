@@ -0,0 +1,213 @@
+// CALL / STATICCALL：在子上下文中执行被调用合约的代码
+//
+// 被调用方用一个新的 EVM 实例执行，账户数据库在调用期间移交给它，调用结束后收回；
+// 调用失败（REVERT 或异常停止）时账户状态和 warm 地址/存储槽回滚到调用前的快照。
+//
+// 测试某个合约时可以不实现它依赖的合约：用 `register_call_response` 预先登记
+// `(地址, 输入) -> (是否成功, 输出)`，命中登记的调用直接返回登记的结果，不执行任何代码。
+
+use crate::*;
+
+// 调用深度上限，超过时 CALL 直接失败（压入 0）
+pub(crate) const CALL_DEPTH_LIMIT: usize = 1024;
+
+// 转账 value 非 0 时的附加费用，以及随调用免费附送给被调用方的 gas
+pub(crate) const GAS_CALL_VALUE: u64 = 9000;
+pub(crate) const GAS_CALL_STIPEND: u64 = 2300;
+
+/// 预先登记的外部调用结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallResponse {
+    pub success: bool,
+    pub output: Vec<u8>,
+}
+
+// 一次子调用的结果
+struct CallOutcome {
+    success: bool,
+    output: Vec<u8>,
+    // 调用方需要为这次调用支付的 gas（不含附送的 stipend）
+    gas_used: u64,
+}
+
+impl EVM {
+    /// 登记对 address 以 input 为输入的调用结果，CALL/STATICCALL 命中时直接返回该结果
+    pub fn register_call_response(&mut self, address: Address, input: Vec<u8>, success: bool, output: Vec<u8>) {
+        self.call_responses.insert((address, input), CallResponse { success, output });
+    }
+
+    // CALL：gas, addr, value, argsOffset, argsLength, retOffset, retLength
+    pub(crate) fn call(&mut self) -> Result<(), EvmError> {
+        self.underflow_judge(7)?;
+        let gas = self.pop()?;
+        let addr = self.pop_address()?;
+        let value = self.pop()?;
+        if !value.is_zero() {
+            self.require_non_static()?;
+        }
+        self.call_common(gas, addr, value, false)
+    }
+
+    // STATICCALL：gas, addr, argsOffset, argsLength, retOffset, retLength，被调用方不能修改状态
+    pub(crate) fn static_call(&mut self) -> Result<(), EvmError> {
+        self.underflow_judge(6)?;
+        let gas = self.pop()?;
+        let addr = self.pop_address()?;
+        self.call_common(gas, addr, U256::zero(), true)
+    }
+
+    fn call_common(&mut self, gas: U256, addr: Address, value: U256, is_static: bool) -> Result<(), EvmError> {
+        let args_offset = self.pop_usize()?;
+        let args_length = self.pop_usize()?;
+        let ret_offset = self.pop_usize()?;
+        let ret_length = self.pop_usize()?;
+
+        self.ensure_memory(args_offset, args_length)?;
+        self.ensure_memory(ret_offset, ret_length)?;
+        self.access_address(addr)?;
+        if !value.is_zero() {
+            self.use_gas(GAS_CALL_VALUE, GasCategory::Calls)?;
+        }
+        self.record_memory_read(args_offset, args_length);
+        let input = self.memory_slice(args_offset, args_length).to_vec();
+
+        let outcome = if let Some(response) = self.call_responses.get(&(addr, input.clone())).cloned() {
+            println!("  调用 {:?} 命中登记的结果", addr);
+            self.registered_call(addr, value, response)
+        } else {
+            let gas_limit = self.call_gas(gas);
+            self.execute_call(addr, value, input, gas_limit, is_static || self.is_static)
+        };
+        self.use_gas(outcome.gas_used, GasCategory::Calls)?;
+
+        // 返回数据只复制 retLength 与实际输出中较短的部分
+        let copy_len = ret_length.min(outcome.output.len());
+        if copy_len > 0 {
+            self.memory[ret_offset..ret_offset + copy_len].copy_from_slice(&outcome.output[..copy_len]);
+            self.record_memory_write(ret_offset, copy_len);
+        }
+        self.return_data = outcome.output;
+        self.stack.push(if outcome.success { U256::one() } else { U256::zero() });
+        Ok(())
+    }
+
+    // 命中登记结果的调用：不执行代码，但和真实调用一样转账，登记为失败时不转账
+    // 访问地址和转账的费用在调用前已经收取，被调用方不消耗转发的 gas
+    fn registered_call(&mut self, addr: Address, value: U256, response: CallResponse) -> CallOutcome {
+        if self.depth >= CALL_DEPTH_LIMIT {
+            return CallOutcome { success: false, output: Vec::new(), gas_used: 0 };
+        }
+        if response.success && self.transfer_value(self.address, addr, value).is_err() {
+            return CallOutcome { success: false, output: Vec::new(), gas_used: 0 };
+        }
+        CallOutcome { success: response.success, output: response.output, gas_used: 0 }
+    }
+
+    // 在子上下文中执行 addr 的代码，失败时回滚账户状态
+    fn execute_call(&mut self, addr: Address, value: U256, input: Vec<u8>, gas_limit: u64, is_static: bool) -> CallOutcome {
+        let failed = CallOutcome { success: false, output: Vec::new(), gas_used: 0 };
+        if self.depth >= CALL_DEPTH_LIMIT {
+            return failed;
+        }
+        // 当前合约的存储放回账户数据库，被调用方（包括重入的自己）能看到最新的值
+        let own_storage = std::mem::take(&mut self.storage);
+        self.account_db.entry(self.address).or_default().storage = own_storage;
+        let snapshot = (self.account_db.clone(), self.accessed_addresses.clone(), self.accessed_storage_keys.clone());
+
+        // 余额不足时调用直接失败（压入 0），transfer_value 失败时不会修改任何余额
+        if self.transfer_value(self.address, addr, value).is_err() {
+            self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+            return failed;
+        }
+        let stipend = if value.is_zero() { 0 } else { GAS_CALL_STIPEND };
+
+        if let Some(required) = precompile::gas_cost(&addr) {
+            let output = if gas_limit + stipend >= required { precompile::execute(&addr, &input) } else { None };
+            let outcome = match output {
+                Some(Ok(output)) => CallOutcome { success: true, output, gas_used: required.saturating_sub(stipend) },
+                _ => {
+                    (self.account_db, self.accessed_addresses, self.accessed_storage_keys) = snapshot;
+                    CallOutcome { success: false, output: Vec::new(), gas_used: gas_limit }
+                }
+            };
+            self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+            return outcome;
+        }
+
+        // 没有代码的账户（包括不存在的账户）直接成功，不进入子上下文，也不会为它创建空账户
+        let code = self.account_db.get(&addr).map(|account| account.code.clone()).unwrap_or_default();
+        if code.is_empty() {
+            self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+            return CallOutcome { success: true, output: Vec::new(), gas_used: 0 };
+        }
+        let mut child = self.enter_child(code, addr, value, input, gas_limit + stipend, is_static);
+        let result = child.run();
+        self.leave_child(&mut child);
+
+        // 被调用方的停止方式决定调用结果：
+        //   STOP（或代码执行完）  成功，返回数据为空
+        //   RETURN                成功，返回数据为 RETURN 的内容
+        //   REVERT                失败，返回数据为 REVERT 的内容，未用完的 gas 退还
+        //   异常停止              失败，返回数据为空，转发的 gas 全部消耗
+        // 异常停止包括指令出错，以及 INVALID 等让执行失败但不是 REVERT 的指令；
+        // 子上下文的 output 初始为空，只有 RETURN/REVERT 会写入，所以 STOP 时一定为空；
+        // 子上下文自己发起的调用的返回数据在 return_data 中，不会当作它的输出
+        let success = result.is_ok() && child.success;
+        let reverted = result.is_ok() && child.reverted;
+        let output = if success || reverted { std::mem::take(&mut child.output) } else { Vec::new() };
+        if success {
+            self.logs.append(&mut child.logs);
+        } else {
+            (self.account_db, self.accessed_addresses, self.accessed_storage_keys) = snapshot;
+        }
+        self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+
+        let gas_used = if success || reverted { child.gas_used().saturating_sub(stipend) } else { gas_limit };
+        CallOutcome { success, output, gas_used }
+    }
+
+    // 创建以 address 身份执行 code 的子上下文，账户数据库、warm 集合、登记的调用结果和跳转表缓存移交给它
+    pub(crate) fn enter_child(
+        &mut self,
+        code: Vec<u8>,
+        address: Address,
+        value: U256,
+        input: Vec<u8>,
+        gas_limit: u64,
+        is_static: bool,
+    ) -> EVM {
+        let jump_table = self.code_cache.jump_table(&code);
+        let mut child = EVM::with_jump_table(code, jump_table)
+            .with_caller(self.address)
+            .with_value(value)
+            .with_calldata(input)
+            .with_block_env(self.block_env.clone())
+            .with_gas_price(self.gas_price)
+            .with_gas_limit(gas_limit)
+            .with_max_return_data_size(self.max_return_data_size)
+            .with_max_memory_size(self.max_memory_size)
+            .with_trap_div_by_zero(self.trap_div_by_zero)
+            .with_unknown_opcode_policy(self.unknown_opcode_policy)
+            .with_strict_addresses(self.strict_addresses)
+            .with_static(is_static);
+        child.address = address;
+        child.depth = self.depth + 1;
+        child.storage = std::mem::take(&mut self.account_db.entry(address).or_default().storage);
+        child.account_db = std::mem::take(&mut self.account_db);
+        child.accessed_addresses = std::mem::take(&mut self.accessed_addresses);
+        child.accessed_storage_keys = std::mem::take(&mut self.accessed_storage_keys);
+        child.call_responses = std::mem::take(&mut self.call_responses);
+        child.code_cache = std::mem::take(&mut self.code_cache);
+        child
+    }
+
+    // 子上下文执行结束后收回移交出去的状态，子上下文的存储写回它的账户
+    pub(crate) fn leave_child(&mut self, child: &mut EVM) {
+        child.account_db.entry(child.address).or_default().storage = std::mem::take(&mut child.storage);
+        self.account_db = std::mem::take(&mut child.account_db);
+        self.accessed_addresses = std::mem::take(&mut child.accessed_addresses);
+        self.accessed_storage_keys = std::mem::take(&mut child.accessed_storage_keys);
+        self.call_responses = std::mem::take(&mut child.call_responses);
+        self.code_cache = std::mem::take(&mut child.code_cache);
+    }
+}
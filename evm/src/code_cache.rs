@@ -0,0 +1,84 @@
+// 按代码哈希缓存跳转表：同一份代码被多次执行（例如反复 CALL 同一个合约）时，
+// 只需扫描一次 JUMPDEST
+//
+//   let mut cache = CodeCache::new();
+//   let mut evm = EVM::with_code_cache(code, &mut cache);
+//
+// 每个 EVM 自带一个缓存（`EVM::code_cache`），CALL/CREATE 创建子上下文时从中取跳转表，
+// 并把缓存交给子上下文，嵌套调用也共享同一份。
+
+use crate::EVM;
+use ethereum_types::H256;
+use sha3::{Digest, Keccak256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 一份字节码中所有合法的跳转目标（JUMPDEST 的偏移量）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JumpTable {
+    destinations: HashSet<usize>,
+}
+
+impl JumpTable {
+    /// 扫描字节码，跳过 PUSH 的立即数
+    pub fn analyze(code: &[u8]) -> Self {
+        Self { destinations: EVM::analyze_jump_destinations(code) }
+    }
+
+    pub fn contains(&self, pc: usize) -> bool {
+        self.destinations.contains(&pc)
+    }
+
+    /// 所有跳转目标（升序）
+    pub fn sorted(&self) -> Vec<usize> {
+        let mut destinations: Vec<usize> = self.destinations.iter().copied().collect();
+        destinations.sort_unstable();
+        destinations
+    }
+}
+
+/// keccak256(code) -> 跳转表，不同代码的哈希不同，所以不会取到别的代码的跳转表
+#[derive(Debug, Clone, Default)]
+pub struct CodeCache {
+    entries: HashMap<H256, Arc<JumpTable>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl CodeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取出代码对应的跳转表，缓存中没有时扫描一次并存入
+    pub fn jump_table(&mut self, code: &[u8]) -> Arc<JumpTable> {
+        let hash = H256::from_slice(&Keccak256::digest(code));
+        if let Some(table) = self.entries.get(&hash) {
+            self.hits += 1;
+            return Arc::clone(table);
+        }
+        self.misses += 1;
+        let table = Arc::new(JumpTable::analyze(code));
+        self.entries.insert(hash, Arc::clone(&table));
+        table
+    }
+
+    /// 缓存中不同代码的数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 命中缓存的次数
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// 未命中、需要重新扫描的次数
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
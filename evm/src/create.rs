@@ -0,0 +1,98 @@
+// CREATE：执行初始化代码，把它返回的数据部署为新合约的代码
+//
+// 新合约地址 = keccak256(rlp([sender, nonce]))[12..]，nonce 为 sender 执行 CREATE 前的 nonce。
+// 通过深度和余额检查后 sender 的 nonce 立即加一，初始化代码失败也不回滚；
+// 新合约的 nonce 从 1 开始，所以初始化代码里再执行 CREATE 时用的是新合约自己的 nonce。
+// 初始化代码与 CALL 一样在子上下文中执行，失败时账户状态回滚到执行前的快照。
+
+use crate::call::CALL_DEPTH_LIMIT;
+use crate::*;
+
+// 部署代码的大小上限（EIP-170）和每字节的部署费用
+pub(crate) const MAX_CODE_SIZE: usize = 24576;
+pub(crate) const GAS_CODE_DEPOSIT: u64 = 200;
+
+impl EVM {
+    /// sender 以 nonce 执行 CREATE 时得到的新合约地址
+    pub fn create_address(sender: Address, nonce: U256) -> Address {
+        let encoded = rlp::encode_list(&[rlp::encode_bytes(sender.as_bytes()), rlp::encode_u256(nonce)]);
+        Address::from_slice(&Keccak256::digest(encoded)[12..])
+    }
+
+    // CREATE：value, offset, length，成功时压入新合约地址，失败时压入 0
+    pub(crate) fn create(&mut self) -> Result<(), EvmError> {
+        self.underflow_judge(3)?;
+        self.require_non_static()?;
+        let value = self.pop()?;
+        let offset = self.pop_usize()?;
+        let length = self.pop_usize()?;
+        self.ensure_memory(offset, length)?;
+        self.record_memory_read(offset, length);
+        let init_code = self.memory_slice(offset, length).to_vec();
+
+        // 与 CALL 相同，最多转发剩余 gas 的 63/64
+        let gas_limit = self.call_gas(U256::MAX);
+        let (address, gas_used) = self.execute_create(value, init_code, gas_limit);
+        self.use_gas(gas_used, GasCategory::Calls)?;
+        self.stack.push(address.map_or(U256::zero(), |address| U256::from_big_endian(address.as_bytes())));
+        Ok(())
+    }
+
+    // 执行初始化代码并部署，返回新合约地址（失败时为 None）和需要支付的 gas
+    fn execute_create(&mut self, value: U256, init_code: Vec<u8>, gas_limit: u64) -> (Option<Address>, u64) {
+        self.return_data.clear();
+        if self.depth >= CALL_DEPTH_LIMIT || self.balance_of(self.address) < value {
+            return (None, 0);
+        }
+        let nonce = self.nonce(self.address);
+        self.increment_nonce(self.address);
+        let address = Self::create_address(self.address, nonce);
+        self.accessed_addresses.insert(address);
+        // 地址冲突：不执行初始化代码，转发的 gas 全部消耗
+        if self.create_collision(address) {
+            return (None, gas_limit);
+        }
+
+        let own_storage = std::mem::take(&mut self.storage);
+        self.account_db.entry(self.address).or_default().storage = own_storage;
+        let snapshot = (self.account_db.clone(), self.accessed_addresses.clone(), self.accessed_storage_keys.clone());
+
+        self.account_db.entry(address).or_default().nonce = U256::one();
+        if self.transfer_value(self.address, address, value).is_err() {
+            (self.account_db, self.accessed_addresses, self.accessed_storage_keys) = snapshot;
+            self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+            return (None, 0);
+        }
+
+        let mut child = self.enter_child(init_code, address, value, Vec::new(), gas_limit, false);
+        let result = child.run();
+        self.leave_child(&mut child);
+
+        // 初始化代码成功结束后，RETURN 的数据就是要部署的代码，按字节支付部署费用
+        let code = std::mem::take(&mut child.output);
+        let deposit = GAS_CODE_DEPOSIT * code.len() as u64;
+        let reverted = result.is_ok() && child.reverted;
+        let deployed = result.is_ok() && child.success && code.len() <= MAX_CODE_SIZE && child.gas_remaining() >= deposit;
+        if deployed {
+            self.set_code(address, code);
+            self.logs.append(&mut child.logs);
+        } else {
+            (self.account_db, self.accessed_addresses, self.accessed_storage_keys) = snapshot;
+            // REVERT 的数据可以通过 RETURNDATA* 读取
+            if reverted {
+                self.return_data = code;
+            }
+        }
+        self.storage = std::mem::take(&mut self.account_db.entry(self.address).or_default().storage);
+
+        // REVERT 退还未用完的 gas，其余失败（异常停止、代码过大、不够支付部署费用）消耗全部转发的 gas
+        let gas_used = if deployed {
+            child.gas_used() + deposit
+        } else if reverted {
+            child.gas_used()
+        } else {
+            gas_limit
+        };
+        (deployed.then_some(address), gas_used)
+    }
+}
@@ -0,0 +1,105 @@
+// 比较两个 EVM 的状态：堆栈、内存、存储槽、日志、余额和 gas
+//
+//   let before = evm.clone();
+//   evm.run()?;
+//   println!("{}", diff_state(&before, &evm));
+
+use crate::EVM;
+use ethereum_types::Address;
+use primitive_types::U256;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// 单个存储槽的变化，不存在的槽按 0 处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotDiff {
+    pub key: U256,
+    pub before: U256,
+    pub after: U256,
+}
+
+/// 单个账户余额的变化，不存在的账户按 0 处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDiff {
+    pub address: Address,
+    pub before: U256,
+    pub after: U256,
+}
+
+/// 两个 EVM 状态之间的差异，字段为 None 或空表示该部分相同
+/// 存储槽和余额按 key/地址排序，与 HashMap 的遍历顺序无关
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub stack: Option<(Vec<U256>, Vec<U256>)>,
+    pub memory: Option<(Vec<u8>, Vec<u8>)>,
+    pub storage: Vec<SlotDiff>,
+    // 日志内容不同时记录两边的日志条数
+    pub logs: Option<(usize, usize)>,
+    pub balances: Vec<BalanceDiff>,
+    pub gas_used: Option<(u64, u64)>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == StateDiff::default()
+    }
+}
+
+/// 计算从 a 到 b 的状态变化
+pub fn diff_state(a: &EVM, b: &EVM) -> StateDiff {
+    let stack = (a.stack != b.stack).then(|| (a.stack.clone(), b.stack.clone()));
+    let memory = (a.memory != b.memory).then(|| (a.memory.clone(), b.memory.clone()));
+
+    let keys: BTreeSet<U256> = a.storage.keys().chain(b.storage.keys()).copied().collect();
+    let storage = keys
+        .into_iter()
+        .filter_map(|key| {
+            let before = a.storage.get(&key).copied().unwrap_or_default();
+            let after = b.storage.get(&key).copied().unwrap_or_default();
+            (before != after).then_some(SlotDiff { key, before, after })
+        })
+        .collect();
+
+    let logs = (a.logs != b.logs).then_some((a.logs.len(), b.logs.len()));
+
+    let addresses: BTreeSet<Address> = a.account_db.keys().chain(b.account_db.keys()).copied().collect();
+    let balances = addresses
+        .into_iter()
+        .filter_map(|address| {
+            let before = a.balance_of(address);
+            let after = b.balance_of(address);
+            (before != after).then_some(BalanceDiff { address, before, after })
+        })
+        .collect();
+
+    let gas_used = (a.gas_used() != b.gas_used()).then_some((a.gas_used(), b.gas_used()));
+
+    StateDiff { stack, memory, storage, logs, balances, gas_used }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "状态无变化");
+        }
+        if let Some((before, after)) = &self.stack {
+            writeln!(f, "堆栈：{:?} -> {:?}", before, after)?;
+        }
+        if let Some((before, after)) = &self.memory {
+            writeln!(f, "内存：0x{} -> 0x{}", hex::encode(before), hex::encode(after))?;
+        }
+        for slot in &self.storage {
+            writeln!(f, "存储槽 {}：{} -> {}", slot.key, slot.before, slot.after)?;
+        }
+        if let Some((before, after)) = self.logs {
+            writeln!(f, "日志：{} 条 -> {} 条（内容不同）", before, after)?;
+        }
+        for balance in &self.balances {
+            writeln!(f, "余额 {:?}：{} -> {}", balance.address, balance.before, balance.after)?;
+        }
+        if let Some((before, after)) = self.gas_used {
+            writeln!(f, "Gas消耗：{} -> {}", before, after)?;
+        }
+        Ok(())
+    }
+}
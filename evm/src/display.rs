@@ -0,0 +1,115 @@
+// 最终状态的显示选项：进制、是否按有符号数解释、堆栈最多显示几项、内存的分组方式
+//
+//   let opts = DisplayOptions::new().with_base(NumberBase::Hex).with_signed(true);
+//   println!("{}", evm.fmt_with(&opts));
+
+use crate::{abs_value, is_negative};
+use primitive_types::U256;
+
+/// 堆栈元素的显示进制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberBase {
+    Dec,
+    Hex,
+    // 十进制和十六进制都显示（默认）
+    #[default]
+    Both,
+}
+
+// 内存默认每 32 字节（一个字）一组
+const DEFAULT_MEMORY_GROUP_SIZE: usize = 32;
+
+// 内存每行至少显示的字节数
+const MEMORY_ROW_SIZE: usize = 32;
+
+/// `EVM::fmt_with` 使用的显示选项，默认值与 `Display` 的输出一致
+#[derive(Debug, Clone)]
+pub struct DisplayOptions {
+    pub base: NumberBase,
+    // 按二进制补码解释为有符号数，负数显示为 -x
+    pub signed: bool,
+    // 堆栈最多显示的项数（从栈顶算起），None 表示全部显示
+    pub max_stack_slots: Option<usize>,
+    // 额外显示按地址和 bytes32 解释的结果
+    pub verbose: bool,
+    // 内存十六进制每多少字节一组，组之间用空格分隔，例如 4 便于看出函数选择器
+    pub memory_group_size: usize,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            base: NumberBase::default(),
+            signed: false,
+            max_stack_slots: None,
+            verbose: false,
+            memory_group_size: DEFAULT_MEMORY_GROUP_SIZE,
+        }
+    }
+}
+
+impl DisplayOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_base(mut self, base: NumberBase) -> Self {
+        self.base = base;
+        self
+    }
+
+    pub fn with_signed(mut self, signed: bool) -> Self {
+        self.signed = signed;
+        self
+    }
+
+    pub fn with_max_stack_slots(mut self, max_stack_slots: usize) -> Self {
+        self.max_stack_slots = Some(max_stack_slots);
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// 设置内存分组的字节数，0 按 1 处理
+    pub fn with_memory_group_size(mut self, size: usize) -> Self {
+        self.memory_group_size = size.max(1);
+        self
+    }
+}
+
+/// 按显示选项把内存格式化为若干行：每行 32 字节（分组大于 32 时为一组），
+/// 行内每 memory_group_size 字节一组，组之间用空格分隔
+pub fn format_memory(memory: &[u8], opts: &DisplayOptions) -> Vec<String> {
+    let group_size = opts.memory_group_size.max(1);
+    let row_size = MEMORY_ROW_SIZE.max(group_size);
+    memory
+        .chunks(row_size)
+        .map(|row| row.chunks(group_size).map(hex::encode).collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+/// 按显示选项格式化一个 32 字节的字（堆栈元素）
+pub fn format_word_with(value: U256, opts: &DisplayOptions) -> String {
+    let (sign, magnitude, prefix) = if opts.signed {
+        let sign = if is_negative(value) { "-" } else { "" };
+        (sign, abs_value(value), "有符号")
+    } else {
+        ("", value, "")
+    };
+    let dec = format!("{}十进制={}{}", prefix, sign, magnitude);
+    let hex = format!("{}十六进制={}0x{:x}", prefix, sign, magnitude);
+    let mut text = match opts.base {
+        NumberBase::Dec => dec,
+        NumberBase::Hex => hex,
+        NumberBase::Both => format!("默认{}, {}", dec, hex),
+    };
+    if opts.verbose {
+        let mut buf = [0u8; 32];
+        value.to_big_endian(&mut buf);
+        text.push_str(&format!(", 地址=0x{}, bytes32=0x{}", hex::encode(&buf[12..]), hex::encode(buf)));
+    }
+    text
+}
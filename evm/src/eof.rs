@@ -0,0 +1,221 @@
+// EIP-3540 EOF（EVM Object Format）容器解析，仅做结构校验，不负责执行
+//
+// 容器布局：
+//   header: magic(0xEF00) version(0x01)
+//           kind_types(0x01) types_size(u16)
+//           kind_code(0x02) num_code_sections(u16) code_size(u16)*num_code_sections
+//           kind_data(0x04) data_size(u16)
+//           terminator(0x00)
+//   body:   types_section code_section+ data_section
+
+use std::fmt;
+
+const EOF_MAGIC: [u8; 2] = [0xEF, 0x00];
+const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_DATA: u8 = 0x04;
+const TERMINATOR: u8 = 0x00;
+
+// 每个代码段在 types 段中占 4 字节：inputs(u8) outputs(u8) max_stack_height(u16)
+const TYPE_ENTRY_SIZE: usize = 4;
+const MAX_CODE_SECTIONS: usize = 1024;
+const MAX_INPUTS: u8 = 0x7F;
+const MAX_OUTPUTS: u8 = 0x7F;
+// 不返回的代码段 outputs 固定为 0x80
+const NON_RETURNING: u8 = 0x80;
+const MAX_STACK_HEIGHT: u16 = 1023;
+
+/// EOF 容器校验失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EofError {
+    // 开头不是 0xEF00
+    InvalidMagic,
+    // 不支持的版本号
+    UnsupportedVersion(u8),
+    // 读取 header 时字节不足
+    IncompleteHeader,
+    // 遇到的段类型与预期不符
+    UnexpectedSectionKind { expected: u8, found: u8 },
+    // header 结尾缺少 0x00 终止符
+    MissingTerminator(u8),
+    // 代码段数量为 0 或超过上限
+    InvalidCodeSectionCount(usize),
+    // 某个代码段长度为 0
+    EmptyCodeSection(usize),
+    // types 段大小与代码段数量不匹配
+    InvalidTypesSize { expected: usize, found: usize },
+    // 代码段的 inputs/outputs/max_stack_height 越界
+    InvalidTypeEntry { section: usize, reason: &'static str },
+    // 第一个代码段必须是 0 输入、不返回
+    InvalidFirstSectionType,
+    // body 长度与 header 声明的不一致
+    BodySizeMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for EofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EofError::InvalidMagic => write!(f, "EOF 容器必须以 0xEF00 开头"),
+            EofError::UnsupportedVersion(v) => write!(f, "不支持的 EOF 版本：0x{:02x}", v),
+            EofError::IncompleteHeader => write!(f, "EOF header 字节不足"),
+            EofError::UnexpectedSectionKind { expected, found } => {
+                write!(f, "段类型错误，期望 0x{:02x}，实际 0x{:02x}", expected, found)
+            }
+            EofError::MissingTerminator(found) => {
+                write!(f, "header 缺少终止符 0x00，实际为 0x{:02x}", found)
+            }
+            EofError::InvalidCodeSectionCount(n) => write!(f, "代码段数量非法：{}", n),
+            EofError::EmptyCodeSection(i) => write!(f, "第 {} 个代码段长度为 0", i),
+            EofError::InvalidTypesSize { expected, found } => {
+                write!(f, "types 段大小错误，期望 {} 字节，实际 {} 字节", expected, found)
+            }
+            EofError::InvalidTypeEntry { section, reason } => {
+                write!(f, "第 {} 个代码段类型非法：{}", section, reason)
+            }
+            EofError::InvalidFirstSectionType => {
+                write!(f, "第一个代码段必须是 0 输入且不返回（outputs = 0x80）")
+            }
+            EofError::BodySizeMismatch { expected, found } => {
+                write!(f, "body 长度与 header 不一致，期望 {} 字节，实际 {} 字节", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EofError {}
+
+/// 代码段的类型信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeSection {
+    pub inputs: u8,
+    pub outputs: u8,
+    pub max_stack_height: u16,
+}
+
+/// 解析后的 EOF 容器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EofContainer {
+    pub version: u8,
+    pub types: Vec<TypeSection>,
+    pub code_sections: Vec<Vec<u8>>,
+    pub data: Vec<u8>,
+}
+
+/// 判断字节码是否以 EOF magic 开头
+pub fn is_eof(code: &[u8]) -> bool {
+    code.starts_with(&EOF_MAGIC)
+}
+
+// 按顺序读取 header 字节的游标
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn u8(&mut self) -> Result<u8, EofError> {
+        let byte = *self.bytes.get(self.pos).ok_or(EofError::IncompleteHeader)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, EofError> {
+        let hi = self.u8()?;
+        let lo = self.u8()?;
+        Ok(u16::from_be_bytes([hi, lo]))
+    }
+
+    fn expect_kind(&mut self, expected: u8) -> Result<(), EofError> {
+        let found = self.u8()?;
+        if found != expected {
+            return Err(EofError::UnexpectedSectionKind { expected, found });
+        }
+        Ok(())
+    }
+}
+
+impl EofContainer {
+    /// 解析并校验 EOF 容器
+    pub fn parse(bytes: &[u8]) -> Result<Self, EofError> {
+        if !is_eof(bytes) {
+            return Err(EofError::InvalidMagic);
+        }
+        let mut reader = Reader { bytes, pos: EOF_MAGIC.len() };
+
+        let version = reader.u8()?;
+        if version != EOF_VERSION {
+            return Err(EofError::UnsupportedVersion(version));
+        }
+
+        reader.expect_kind(KIND_TYPES)?;
+        let types_size = reader.u16()? as usize;
+
+        reader.expect_kind(KIND_CODE)?;
+        let num_code_sections = reader.u16()? as usize;
+        if num_code_sections == 0 || num_code_sections > MAX_CODE_SECTIONS {
+            return Err(EofError::InvalidCodeSectionCount(num_code_sections));
+        }
+        let mut code_sizes = Vec::with_capacity(num_code_sections);
+        for i in 0..num_code_sections {
+            let size = reader.u16()? as usize;
+            if size == 0 {
+                return Err(EofError::EmptyCodeSection(i));
+            }
+            code_sizes.push(size);
+        }
+
+        reader.expect_kind(KIND_DATA)?;
+        let data_size = reader.u16()? as usize;
+
+        let terminator = reader.u8()?;
+        if terminator != TERMINATOR {
+            return Err(EofError::MissingTerminator(terminator));
+        }
+
+        let expected_types_size = num_code_sections * TYPE_ENTRY_SIZE;
+        if types_size != expected_types_size {
+            return Err(EofError::InvalidTypesSize { expected: expected_types_size, found: types_size });
+        }
+
+        // header 之后的 body 长度必须与声明的各段大小之和完全一致
+        let body = &bytes[reader.pos..];
+        let expected_body_size = types_size + code_sizes.iter().sum::<usize>() + data_size;
+        if body.len() != expected_body_size {
+            return Err(EofError::BodySizeMismatch { expected: expected_body_size, found: body.len() });
+        }
+
+        let mut types = Vec::with_capacity(num_code_sections);
+        for (i, entry) in body[..types_size].chunks(TYPE_ENTRY_SIZE).enumerate() {
+            let type_section = TypeSection {
+                inputs: entry[0],
+                outputs: entry[1],
+                max_stack_height: u16::from_be_bytes([entry[2], entry[3]]),
+            };
+            if type_section.inputs > MAX_INPUTS {
+                return Err(EofError::InvalidTypeEntry { section: i, reason: "inputs 超过 127" });
+            }
+            if type_section.outputs > MAX_OUTPUTS && type_section.outputs != NON_RETURNING {
+                return Err(EofError::InvalidTypeEntry { section: i, reason: "outputs 超过 127" });
+            }
+            if type_section.max_stack_height > MAX_STACK_HEIGHT {
+                return Err(EofError::InvalidTypeEntry { section: i, reason: "max_stack_height 超过 1023" });
+            }
+            types.push(type_section);
+        }
+        if types[0].inputs != 0 || types[0].outputs != NON_RETURNING {
+            return Err(EofError::InvalidFirstSectionType);
+        }
+
+        let mut offset = types_size;
+        let mut code_sections = Vec::with_capacity(num_code_sections);
+        for size in code_sizes {
+            code_sections.push(body[offset..offset + size].to_vec());
+            offset += size;
+        }
+        let data = body[offset..].to_vec();
+
+        Ok(EofContainer { version, types, code_sections, data })
+    }
+}
@@ -0,0 +1,87 @@
+use primitive_types::U256;
+use std::fmt;
+use std::time::Duration;
+
+/// EVM 执行过程中可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvmError {
+    // 堆栈元素不足
+    StackUnderflow { required: usize, actual: usize },
+    // 剩余 gas 不足以支付本次操作
+    OutOfGas { required: u64, remaining: u64 },
+    // PUSH 指令后的立即数字节不足
+    IncompletePush { required: usize, remaining: usize },
+    // 除数为 0
+    DivisionByZero,
+    // 跳转目标不是合法的 JUMPDEST
+    InvalidJump(usize),
+    // 内存偏移量或长度计算溢出
+    MemoryOverflow,
+    // 读取的返回数据超出范围
+    ReturnDataOutOfBounds,
+    // RETURN/REVERT 的数据长度超过配置的上限
+    ReturnDataTooLarge { size: usize, limit: usize },
+    // 静态上下文中尝试修改状态
+    StaticStateChange,
+    // 十六进制字节码解析失败
+    InvalidHex(String),
+    // 环境变量的值无法解析
+    InvalidEnvVar { name: String, value: String },
+    // 执行时间超过配置的上限
+    Timeout { limit: Duration },
+    // 未实现或不存在的 opcode
+    InvalidOpcode(u8),
+    // 交易的 gas 上限加上区块已用 gas 超过区块 gas 上限，交易未执行
+    BlockGasLimitExceeded { tx_gas_limit: u64, block_gas_used: u64, block_gas_limit: u64 },
+    // 转账金额超过付款方余额
+    InsufficientBalance { required: U256, available: U256 },
+    // 转账后收款方余额超出 U256
+    BalanceOverflow,
+    // 交易愿意支付的最高 gas 单价低于区块的 basefee
+    FeeCapTooLow { fee_cap: U256, base_fee: U256 },
+    // 严格地址模式下，地址参数的高 96 位不为 0
+    DirtyAddress(U256),
+}
+
+impl fmt::Display for EvmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvmError::StackUnderflow { required, actual } => {
+                write!(f, "堆栈下溢，至少需要{}元素, 当前{}个元素", required, actual)
+            }
+            EvmError::OutOfGas { required, remaining } => {
+                write!(f, "gas不足，需要{}，剩余{}", required, remaining)
+            }
+            EvmError::IncompletePush { required, remaining } => {
+                write!(f, "PUSH 指令字节不足，需要{}字节，剩余{}字节", required, remaining)
+            }
+            EvmError::DivisionByZero => write!(f, "不允许除0操作"),
+            EvmError::InvalidJump(destination) => write!(f, "无效的跳转目标：{}", destination),
+            EvmError::MemoryOverflow => write!(f, "memory size overflow"),
+            EvmError::ReturnDataOutOfBounds => write!(f, "data size overflow"),
+            EvmError::ReturnDataTooLarge { size, limit } => {
+                write!(f, "返回数据过大，长度{}字节，上限{}字节", size, limit)
+            }
+            EvmError::StaticStateChange => write!(f, "静态上下文中不允许修改状态"),
+            EvmError::InvalidHex(msg) => write!(f, "十六进制解析失败：{}", msg),
+            EvmError::InvalidEnvVar { name, value } => {
+                write!(f, "环境变量 {} 的值 `{}` 无法解析", name, value)
+            }
+            EvmError::Timeout { limit } => write!(f, "执行超时，上限{:?}", limit),
+            EvmError::InvalidOpcode(op) => write!(f, "无效的opcode：0x{:02x}", op),
+            EvmError::BlockGasLimitExceeded { tx_gas_limit, block_gas_used, block_gas_limit } => {
+                write!(f, "超出区块 gas 上限，交易 gas 上限{}，区块已用{}，区块上限{}", tx_gas_limit, block_gas_used, block_gas_limit)
+            }
+            EvmError::InsufficientBalance { required, available } => {
+                write!(f, "余额不足，需要{}，当前{}", required, available)
+            }
+            EvmError::BalanceOverflow => write!(f, "收款方余额溢出"),
+            EvmError::FeeCapTooLow { fee_cap, base_fee } => {
+                write!(f, "gas 单价上限{}低于区块 basefee {}", fee_cap, base_fee)
+            }
+            EvmError::DirtyAddress(value) => write!(f, "地址参数0x{:x}超出 160 位", value),
+        }
+    }
+}
+
+impl std::error::Error for EvmError {}
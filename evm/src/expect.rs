@@ -0,0 +1,117 @@
+// 测试辅助：用链式 API 描述期望的执行后状态，一次性校验
+//
+//   StateExpectation::new()
+//       .stack([U256::from(3)])
+//       .storage(U256::zero(), U256::from(1))
+//       .assert(&evm);
+
+use crate::EVM;
+use primitive_types::U256;
+
+/// 期望的执行后状态，只校验设置过的项
+#[derive(Debug, Default)]
+pub struct StateExpectation {
+    stack: Option<Vec<U256>>,
+    storage: Vec<(U256, U256)>,
+    memory: Vec<(usize, Vec<u8>)>,
+    log_count: Option<usize>,
+    gas_used: Option<u64>,
+}
+
+impl StateExpectation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 期望的完整堆栈（栈底——>栈顶）
+    pub fn stack(mut self, stack: impl IntoIterator<Item = U256>) -> Self {
+        self.stack = Some(stack.into_iter().collect());
+        self
+    }
+
+    /// 期望某个存储槽的值，可多次调用
+    pub fn storage(mut self, key: U256, value: U256) -> Self {
+        self.storage.push((key, value));
+        self
+    }
+
+    /// 期望从 offset 开始的内存内容，可多次调用
+    pub fn memory_at(mut self, offset: usize, bytes: &[u8]) -> Self {
+        self.memory.push((offset, bytes.to_vec()));
+        self
+    }
+
+    pub fn log_count(mut self, count: usize) -> Self {
+        self.log_count = Some(count);
+        self
+    }
+
+    pub fn gas_used(mut self, gas: u64) -> Self {
+        self.gas_used = Some(gas);
+        self
+    }
+
+    /// 校验所有期望，返回每一项不匹配的说明（多项不匹配时逐行列出）
+    pub fn check(&self, evm: &EVM) -> Result<(), String> {
+        let mut failures = Vec::new();
+
+        if let Some(expected) = &self.stack
+            && evm.stack.as_slice() != expected.as_slice()
+        {
+            failures.push(format!("堆栈不匹配：期望 {:?}，实际 {:?}", expected, evm.stack));
+        }
+
+        for (key, expected) in &self.storage {
+            let actual = evm.storage.get(key).copied().unwrap_or_default();
+            if actual != *expected {
+                failures.push(format!("存储槽 {} 不匹配：期望 {}，实际 {}", key, expected, actual));
+            }
+        }
+
+        for (offset, expected) in &self.memory {
+            let Some(end) = offset.checked_add(expected.len()) else {
+                failures.push(format!(
+                    "内存 [{}..{}+{}) 越界：范围超出 usize",
+                    offset, offset, expected.len()
+                ));
+                continue;
+            };
+            match evm.memory.get(*offset..end) {
+                Some(actual) if actual == expected.as_slice() => {}
+                Some(actual) => failures.push(format!(
+                    "内存 [{}..{}) 不匹配：期望 0x{}，实际 0x{}",
+                    offset, end, hex::encode(expected), hex::encode(actual)
+                )),
+                None => failures.push(format!(
+                    "内存 [{}..{}) 越界：当前内存只有 {} 字节",
+                    offset, end, evm.memory.len()
+                )),
+            }
+        }
+
+        if let Some(expected) = self.log_count
+            && evm.logs.len() != expected
+        {
+            failures.push(format!("日志数量不匹配：期望 {}，实际 {}", expected, evm.logs.len()));
+        }
+
+        if let Some(expected) = self.gas_used
+            && evm.gas_used() != expected
+        {
+            failures.push(format!("gas 消耗不匹配：期望 {}，实际 {}", expected, evm.gas_used()));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("\n"))
+        }
+    }
+
+    /// 校验所有期望，有不匹配时 panic 并列出全部差异
+    pub fn assert(&self, evm: &EVM) {
+        if let Err(message) = self.check(evm) {
+            panic!("执行后状态与期望不符：\n{}", message);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// gas 费用表
+
+use crate::*;
+
+// 日志 gas：基础费用 + 每个 topic 费用 + 每字节数据费用
+pub(crate) const GAS_LOG: u64 = 375;
+pub(crate) const GAS_LOG_TOPIC: u64 = 375;
+pub(crate) const GAS_LOG_DATA: u64 = 8;
+
+// SHA3 每 32 字节（不足按 32 字节算）的费用
+pub(crate) const GAS_SHA3_WORD: u64 = 6;
+
+// SSTORE：槽位从 0 写成非 0 的费用，以及其余情况的费用
+pub(crate) const GAS_SSTORE_SET: u64 = 20000;
+pub(crate) const GAS_SSTORE_RESET: u64 = 2900;
+
+// EIP-2929：访问 warm 地址/槽位的费用，以及首次访问 cold 地址的费用
+pub(crate) const GAS_WARM_ACCESS: u64 = 100;
+pub(crate) const GAS_COLD_ACCOUNT_ACCESS: u64 = 2600;
+// EIP-2929：首次访问 cold 存储槽的费用
+pub(crate) const GAS_COLD_SLOAD: u64 = 2100;
+
+// CREATE 的固定费用
+pub(crate) const GAS_CREATE: u64 = 32000;
+
+/// 指令的固定 gas 费用，不含内存扩展、数据长度等动态部分
+pub fn static_gas(op: u8) -> u64 {
+    match op {
+        STOP | RETURN | REVERT | INVALID | SSTORE | LOG0..=LOG4 => 0,
+        JUMPDEST => 1,
+        ADDRESS | COINBASE | TIMESTAMP | NUMBER | PREVRANDAO | GASLIMIT | CHAINID | BASEFEE | BLOBBASEFEE
+        | POP | PC | MSIZE | PUSH0 | RETURNDATASIZE | CALLER | CALLVALUE | CALLDATASIZE | GASPRICE => 2,
+        ADD | SUB | LT | GT | EQ | AND | OR | NOT | MLOAD | MSTORE | MSTORE8 | RETURNDATACOPY
+        | CALLDATALOAD | CALLDATACOPY
+        | PUSH1..=PUSH32 | DUP1..=DUP16 | SWAP1..=SWAP16 => 3,
+        MUL | DIV | SDIV | MOD | SMOD | SELFBALANCE => 5,
+        JUMP => 8,
+        JUMPI => 10,
+        BLOCKHASH => 20,
+        SHA3 => 30,
+        BALANCE | EXTCODESIZE | EXTCODECOPY | EXTCODEHASH | SLOAD | CALL | STATICCALL => GAS_WARM_ACCESS,
+        CREATE => GAS_CREATE,
+        _ => 0,
+    }
+}
+
+// 内存扩展：每个字（32 字节）3 gas，外加字数平方 / 512
+pub(crate) const GAS_MEMORY_WORD: u64 = 3;
+pub(crate) const GAS_MEMORY_QUAD_DIVISOR: u64 = 512;
+
+/// 内存大小为 size 字节时的总内存费用，扩展时只需支付新旧费用之差
+/// 费用超出 u64 时为 u64::MAX，任何 gas 上限都付不起
+pub fn memory_cost(size: usize) -> u64 {
+    checked_memory_cost(size).unwrap_or(u64::MAX)
+}
+
+// 同 memory_cost，费用超出 u64 时返回 None
+pub(crate) fn checked_memory_cost(size: usize) -> Option<u64> {
+    let words = size.div_ceil(32) as u64;
+    let quadratic = words.checked_mul(words)? / GAS_MEMORY_QUAD_DIVISOR;
+    GAS_MEMORY_WORD.checked_mul(words)?.checked_add(quadratic)
+}
+
+/// gas 的用途分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasCategory {
+    Computation,
+    Memory,
+    Storage,
+    Logs,
+    Calls,
+    AccountAccess,
+}
+
+/// 指令固定费用所属的分类
+pub fn category(op: u8) -> GasCategory {
+    match op {
+        SLOAD | SSTORE => GasCategory::Storage,
+        LOG0..=LOG4 => GasCategory::Logs,
+        CREATE | CALL | STATICCALL => GasCategory::Calls,
+        BALANCE | SELFBALANCE | EXTCODESIZE | EXTCODECOPY | EXTCODEHASH => GasCategory::AccountAccess,
+        _ => GasCategory::Computation,
+    }
+}
+
+/// 按分类累计的 gas 消耗账本
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasLedger {
+    pub computation: u64,
+    pub memory: u64,
+    pub storage: u64,
+    pub logs: u64,
+    pub calls: u64,
+    pub account_access: u64,
+}
+
+impl GasLedger {
+    pub(crate) fn record(&mut self, category: GasCategory, amount: u64) {
+        let bucket = match category {
+            GasCategory::Computation => &mut self.computation,
+            GasCategory::Memory => &mut self.memory,
+            GasCategory::Storage => &mut self.storage,
+            GasCategory::Logs => &mut self.logs,
+            GasCategory::Calls => &mut self.calls,
+            GasCategory::AccountAccess => &mut self.account_access,
+        };
+        *bucket += amount;
+    }
+
+    /// 各分类之和，等于总 gas 消耗
+    pub fn total(&self) -> u64 {
+        self.computation + self.memory + self.storage + self.logs + self.calls + self.account_access
+    }
+}
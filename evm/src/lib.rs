@@ -0,0 +1,1961 @@
+use ethereum_types::{Address, H256};
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+use std::collections::{HashMap,HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub mod asm;
+mod block;
+pub mod builder;
+mod call;
+pub mod code_cache;
+mod create;
+mod diff;
+pub mod display;
+pub mod eof;
+mod error;
+pub mod expect;
+pub mod gas;
+pub mod lint;
+pub mod precompile;
+#[cfg(feature = "profiler")]
+mod profiler;
+pub mod rlp;
+mod step;
+pub mod tracer;
+mod transaction;
+
+pub use block::BlockEnv;
+pub use call::CallResponse;
+pub use code_cache::{CodeCache, JumpTable};
+pub use diff::{diff_state, BalanceDiff, SlotDiff, StateDiff};
+pub use display::{DisplayOptions, NumberBase};
+pub use error::EvmError;
+#[cfg(feature = "profiler")]
+pub use profiler::OpcodeProfiler;
+pub use step::RunState;
+pub use tracer::{HistoryTracer, NoopTracer, Tracer};
+pub use transaction::{execute_block, Receipt, Transaction};
+
+use gas::{
+    GasCategory, GasLedger, GAS_COLD_ACCOUNT_ACCESS, GAS_COLD_SLOAD, GAS_LOG, GAS_LOG_DATA, GAS_LOG_TOPIC,
+    GAS_SHA3_WORD, GAS_SSTORE_RESET, GAS_SSTORE_SET, GAS_WARM_ACCESS,
+};
+use tracer::MemoryAccess;
+
+// EVM 官方opcode常量
+
+// 停止指令
+const STOP: u8 = 0x00;
+
+// 堆栈指令
+const PUSH0: u8 = 0x5F;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7F;
+const POP: u8 = 0x50;
+
+// 算数指令
+const ADD: u8 = 0x01;
+const SUB: u8 = 0x03;
+const MUL: u8 = 0x02;
+const DIV: u8 = 0x04;
+const SDIV: u8 = 0x05;
+const MOD: u8 = 0x06;
+const SMOD: u8 = 0x07;
+
+// 比较指令
+const LT: u8 = 0x10;
+const GT: u8 = 0x11;
+const EQ: u8 = 0x14;
+
+// 位级指令
+const AND: u8 = 0x16;
+const OR: u8 = 0x17;
+const NOT: u8 = 0x19;
+
+// 内存指令
+const MSTORE: u8 = 0x52;
+const MSTORE8: u8 = 0x53;
+const MLOAD: u8 = 0x51;
+const MSIZE: u8 = 0x59;
+
+// 存储指令
+const SSTORE: u8 = 0x55;
+const SLOAD: u8 = 0x54;
+
+// 跳转指令
+const JUMPDEST: u8 = 0x5b;
+const JUMP: u8 = 0x56;
+const JUMPI: u8 = 0x57;
+const PC: u8 = 0x58;
+
+// 区块信息指令
+const BLOCKHASH:u8 = 0x40;
+const COINBASE:u8  = 0x41;
+const TIMESTAMP:u8  = 0x42;
+const NUMBER:u8  = 0x43;
+const PREVRANDAO:u8  = 0x44;
+const GASLIMIT:u8  = 0x45;
+const CHAINID:u8  = 0x46;
+const SELFBALANCE:u8  = 0x47;
+const BASEFEE:u8  = 0x48;
+const BLOBBASEFEE:u8  = 0x4A;
+
+// 堆栈指令2
+const DUP1:u8 = 0x80;
+const DUP16: u8 = 0x8F;
+const SWAP1:u8 = 0x90;
+const SWAP16:u8 = 0x9F;
+
+// SHA3指令
+const SHA3: u8 = 0x20;
+
+// 账户指令
+const ADDRESS:u8 = 0x30;
+const BALANCE:u8 = 0x31;
+const EXTCODESIZE:u8 = 0x3B;
+const EXTCODECOPY:u8 = 0x3C;
+const EXTCODEHASH:u8 = 0x3F;
+
+// 日志指令
+const LOG0: u8 = 0xA0;
+const LOG4: u8 = 0xA4;
+
+// 调用上下文指令
+const CALLER: u8 = 0x33;
+const CALLVALUE: u8 = 0x34;
+const CALLDATALOAD: u8 = 0x35;
+const CALLDATASIZE: u8 = 0x36;
+const CALLDATACOPY: u8 = 0x37;
+const GASPRICE: u8 = 0x3A;
+
+// 返回数据
+const RETURN: u8 = 0xF3;
+const RETURNDATASIZE: u8 = 0x3D;
+const RETURNDATACOPY: u8 = 0x3E;
+
+// 调用指令
+const CREATE: u8 = 0xF0;
+const CALL: u8 = 0xF1;
+const STATICCALL: u8 = 0xFA;
+
+// 回滚指令
+const REVERT: u8 = 0xFD;
+const INVALID: u8 = 0xFE;
+
+// 默认 gas 上限
+const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+// 默认内存上限（32 MiB），防止恶意字节码让宿主分配超大内存
+const DEFAULT_MAX_MEMORY_SIZE: usize = 32 * 1024 * 1024;
+
+// RETURN/REVERT 默认允许返回的最大数据长度（1 MiB）
+const DEFAULT_MAX_RETURN_DATA_SIZE: usize = 1024 * 1024;
+
+// 当前执行合约账户的默认值，可通过 with_address/with_balance/with_nonce 修改
+const DEFAULT_CONTRACT_ADDRESS: &str = "0x9bbfed6889322e016e0a02ee459d306fc19545d8";
+const DEFAULT_CONTRACT_BALANCE: u64 = 100;
+const DEFAULT_CONTRACT_NONCE: u64 = 1;
+
+// 设置了执行时间上限时，每执行多少条指令检查一次时钟
+const TIMEOUT_CHECK_INTERVAL: u64 = 1024;
+
+/// 一次执行的结果汇总
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionResult {
+    pub success: bool,
+    pub stack: Vec<U256>,
+    pub return_data: Vec<u8>,
+    pub gas_used: u64,
+    // 按分类统计的 gas 消耗
+    pub gas_ledger: GasLedger,
+    // 执行中出错时的错误信息
+    pub error: Option<EvmError>,
+}
+
+/// 遇到未实现（或不存在）的 opcode 时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownOpcodePolicy {
+    // 与真实 EVM 一致：立即停止，执行失败并消耗全部剩余 gas
+    #[default]
+    Halt,
+    // 返回 EvmError::InvalidOpcode
+    Error,
+    // 学习模式：打印提示后跳过该字节继续执行，不符合规范
+    Skip,
+}
+
+/// 一笔交易的手续费拆分（EIP-1559）：基础费用被销毁，小费归出块者
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub base_fee_burned: U256,
+    pub priority_fee_paid: U256,
+}
+
+#[allow(dead_code)]
+#[derive(Default, Clone)]
+struct AccountInfo {
+    balance: U256,
+    nonce: U256,
+    storage: HashMap<U256, U256>,
+    code: Vec<u8>,
+}
+
+/// LOG0~LOG4 产生的一条日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log{
+    // 产生日志的合约地址
+    pub address: Address,
+    pub data: Vec<u8>,
+    pub topics: Vec<H256>,
+}
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone)]
+pub struct EVM {
+    code: Vec<u8>,
+    pc: usize,
+    stack: Vec<U256>, // 存储32字节整数
+    memory: Vec<u8>,
+    storage: HashMap<U256, U256>,
+    jump_destinations: Arc<JumpTable>,
+    block_env: BlockEnv,
+    address: Address, // 当前执行合约的地址
+    account_db: HashMap<Address, AccountInfo>,
+    logs: Vec<Log>,
+    output: Vec<u8>, // 本帧 RETURN/REVERT 的数据，只有这两条指令会写入
+    return_data: Vec<u8>, // 最近一次子调用的返回数据，RETURNDATASIZE/RETURNDATACOPY 读取它
+    success: bool,
+    reverted: bool, // 本帧以 REVERT 结束：与异常停止不同，未用完的 gas 会退还给调用方
+    gas_limit: u64,
+    gas_remaining: u64,
+    max_return_data_size: usize,
+    max_memory_size: usize,
+    trap_div_by_zero: bool,
+    // 当前指令产生的内存读写记录，指令执行完后交给 tracer
+    memory_accesses: Vec<MemoryAccess>,
+    verbose_display: bool,
+    is_static: bool, // 静态上下文（STATICCALL）中禁止修改状态
+    gas_ledger: GasLedger,
+    accessed_addresses: HashSet<Address>, // EIP-2929：本次执行已访问过（warm）的地址
+    accessed_storage_keys: HashSet<(Address, U256)>, // EIP-2929：已访问过（warm）的存储槽
+    max_duration: Option<Duration>, // 执行的墙钟时间上限，None 表示不限制
+    unknown_opcode_policy: UnknownOpcodePolicy,
+    depth: usize, // 调用深度，最外层为 0
+    call_responses: HashMap<(Address, Vec<u8>), CallResponse>, // 预先登记的外部调用结果
+    halted: bool, // 遇到 STOP/RETURN 后停止
+    caller: Address, // 发起本次调用的地址（CALLER）
+    value: U256, // 随调用转入的金额（CALLVALUE）
+    calldata: Vec<u8>, // 调用的输入数据
+    gas_price: U256, // 交易的实际 gas 价格（GASPRICE）
+    strict_addresses: bool, // 地址参数高 96 位非 0 时报错，而不是忽略
+    code_cache: CodeCache, // 子调用执行的代码的跳转表缓存，与子上下文共享
+    undo_depth: usize, // step_back 最多可回退的步数
+    undo_history: step::UndoHistory,
+}
+
+impl EVM{
+    /// code 可以为空：空代码执行时直接在 pc 0 处隐式停止，视为成功
+    pub fn new(code: Vec<u8>) -> Self{
+        let jump_destinations = Arc::new(JumpTable::analyze(&code));
+        Self::with_jump_table(code, jump_destinations)
+    }
+
+    /// 构造一次入口调用：caller 以 value 的金额、calldata 为输入调用 code，其余使用默认值
+    pub fn for_call(code: Vec<u8>, caller: Address, value: U256, calldata: Vec<u8>) -> Self{
+        Self::new(code).with_caller(caller).with_value(value).with_calldata(calldata)
+    }
+
+    /// 与 `new` 相同，但跳转表从缓存中取，同一份代码只扫描一次
+    pub fn with_code_cache(code: Vec<u8>, cache: &mut CodeCache) -> Self{
+        let jump_destinations = cache.jump_table(&code);
+        Self::with_jump_table(code, jump_destinations)
+    }
+
+    fn with_jump_table(code: Vec<u8>, jump_destinations: Arc<JumpTable>) -> Self{
+
+        // 当前执行的合约账户，代码就是正在执行的字节码，EXTCODE* 查询自身时读到的也是它
+        let mut account_db: HashMap<Address, AccountInfo> = HashMap::new();
+        let address = Address::from_str(DEFAULT_CONTRACT_ADDRESS).unwrap();
+        let account_db_info = AccountInfo{
+            balance: U256::from(DEFAULT_CONTRACT_BALANCE),
+            nonce: U256::from(DEFAULT_CONTRACT_NONCE),
+            storage: HashMap::new(),
+            code: code.clone(),
+        };
+        account_db.insert(address, account_db_info);
+
+        Self {
+            code,
+            pc: 0,
+            stack: Vec::new(),
+            memory: Vec::new(),
+            storage: HashMap::new(),
+            jump_destinations,
+            block_env: BlockEnv::default(),
+            address,
+            account_db,
+            logs: Vec::new(),
+            output: Vec::new(),
+            return_data: Vec::new(),
+            success: true,
+            reverted: false,
+            gas_limit: DEFAULT_GAS_LIMIT,
+            gas_remaining: DEFAULT_GAS_LIMIT,
+            max_return_data_size: DEFAULT_MAX_RETURN_DATA_SIZE,
+            max_memory_size: DEFAULT_MAX_MEMORY_SIZE,
+            trap_div_by_zero: false,
+            memory_accesses: Vec::new(),
+            verbose_display: false,
+            is_static: false,
+            gas_ledger: GasLedger::default(),
+            // 当前合约自身从一开始就是 warm
+            accessed_addresses: HashSet::from([address]),
+            accessed_storage_keys: HashSet::new(),
+            max_duration: None,
+            unknown_opcode_policy: UnknownOpcodePolicy::default(),
+            depth: 0,
+            call_responses: HashMap::new(),
+            halted: false,
+            caller: Address::zero(),
+            value: U256::zero(),
+            calldata: Vec::new(),
+            gas_price: U256::zero(),
+            strict_addresses: false,
+            code_cache: CodeCache::new(),
+            undo_depth: step::DEFAULT_UNDO_DEPTH,
+            undo_history: step::UndoHistory::new(),
+        }
+    }
+
+    // 扫描字节码中的 JUMPDEST，跳过 PUSH 的立即数，避免把数据中的 0x5b 当成跳转目标
+    pub(crate) fn analyze_jump_destinations(code: &[u8]) -> HashSet<usize>{
+        let mut jump_destinations = HashSet::new();
+        let mut i = 0;
+        while i < code.len(){
+            let op = code[i];
+            if op == JUMPDEST{
+                jump_destinations.insert(i);
+            }else if (PUSH1..=PUSH32).contains(&op){
+                i += (op - PUSH1 + 1) as usize;
+            }
+            i += 1;
+        }
+        jump_destinations
+    }
+
+    /// 返回所有合法跳转目标的偏移量（升序）
+    pub fn jump_destinations(&self) -> Vec<usize>{
+        self.jump_destinations.sorted()
+    }
+
+    /// 当前代码的跳转表，使用同一个 CodeCache 构造的 EVM 共享同一份
+    pub fn jump_table(&self) -> &Arc<JumpTable>{
+        &self.jump_destinations
+    }
+
+    /// CALL/CREATE 子上下文使用的跳转表缓存，反复调用同一份代码时只扫描一次
+    pub fn code_cache(&self) -> &CodeCache{
+        &self.code_cache
+    }
+
+    /// 替换区块环境，区块信息类指令都会读取这里配置的值
+    pub fn with_block_env(mut self, block_env: BlockEnv) -> Self{
+        self.block_env = block_env;
+        self
+    }
+
+    /// 设置当前执行合约的地址，合约账户（余额、nonce、代码）随之迁移到新地址
+    pub fn with_address(mut self, address: Address) -> Self{
+        let account = self.account_db.remove(&self.address).unwrap_or_default();
+        self.accessed_addresses.remove(&self.address);
+        self.address = address;
+        self.account_db.insert(address, account);
+        self.accessed_addresses.insert(address);
+        self
+    }
+
+    /// 设置调用者地址，CALLER 读取它
+    pub fn with_caller(mut self, caller: Address) -> Self{
+        self.caller = caller;
+        self
+    }
+
+    /// 设置随调用转入的金额，CALLVALUE 读取它（只是调用参数，不会修改任何余额）
+    pub fn with_value(mut self, value: U256) -> Self{
+        self.value = value;
+        self
+    }
+
+    /// 设置调用的输入数据，CALLDATALOAD/CALLDATASIZE/CALLDATACOPY 读取它
+    pub fn with_calldata(mut self, calldata: Vec<u8>) -> Self{
+        self.calldata = calldata;
+        self
+    }
+
+    /// 设置交易的实际 gas 价格，GASPRICE 读取它
+    pub fn with_gas_price(mut self, gas_price: U256) -> Self{
+        self.gas_price = gas_price;
+        self
+    }
+
+    /// 设置当前执行合约的余额，SELFBALANCE 和 BALANCE(ADDRESS) 都读取它
+    pub fn with_balance(mut self, balance: U256) -> Self{
+        self.account_db.entry(self.address).or_default().balance = balance;
+        self
+    }
+
+    /// 设置当前执行合约的 nonce
+    pub fn with_nonce(mut self, nonce: U256) -> Self{
+        self.account_db.entry(self.address).or_default().nonce = nonce;
+        self
+    }
+
+    /// 当前执行合约的地址
+    pub fn address(&self) -> Address{
+        self.address
+    }
+
+    /// 设置本次执行的 gas 上限
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self{
+        self.gas_limit = gas_limit;
+        self.gas_remaining = gas_limit;
+        self
+    }
+
+    /// 测试辅助：预置内存内容，便于单独验证 MLOAD/SHA3/RETURN 等读内存的指令，
+    /// 不必先写一串 MSTORE
+    pub fn with_memory(mut self, memory: Vec<u8>) -> Self{
+        self.memory = memory;
+        self
+    }
+
+    /// 设置 RETURN/REVERT 返回数据的长度上限，防止合约返回超大数据拖垮宿主
+    pub fn with_max_return_data_size(mut self, limit: usize) -> Self{
+        self.max_return_data_size = limit;
+        self
+    }
+
+    /// 设置内存大小上限，超过上限的内存扩展会返回 MemoryOverflow
+    pub fn with_max_memory_size(mut self, limit: usize) -> Self{
+        self.max_memory_size = limit;
+        self
+    }
+
+    /// 学习模式：开启后 DIV/MOD/SDIV/SMOD 的除数为 0 时返回 DivisionByZero，
+    /// 便于发现程序错误。注意这不符合 EVM 规范（规范规定 x / 0 == 0），默认关闭
+    pub fn with_trap_div_by_zero(mut self, enabled: bool) -> Self{
+        self.trap_div_by_zero = enabled;
+        self
+    }
+
+    /// 开启后 Display 中每个堆栈元素还会显示为地址（低 20 字节）和 bytes32
+    pub fn with_verbose_display(mut self, verbose: bool) -> Self{
+        self.verbose_display = verbose;
+        self
+    }
+
+    /// 以静态上下文执行（STATICCALL 的被调用方），SSTORE、LOG 等修改状态的指令会报错
+    pub fn with_static(mut self, is_static: bool) -> Self{
+        self.is_static = is_static;
+        self
+    }
+
+    /// 设置执行的墙钟时间上限，超时后以 `EvmError::Timeout` 停止；
+    /// 每执行 TIMEOUT_CHECK_INTERVAL 条指令检查一次，避免每步都读时钟
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self{
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// 严格地址模式：BALANCE、EXTCODE*、CALL 等指令的地址参数高 96 位非 0 时返回 DirtyAddress。
+    /// 默认按规范只取低 20 字节、忽略高位，严格模式用来发现字节码里拼错的地址
+    pub fn with_strict_addresses(mut self, strict: bool) -> Self{
+        self.strict_addresses = strict;
+        self
+    }
+
+    /// 设置遇到未知 opcode 时的处理方式，默认 Halt
+    pub fn with_unknown_opcode_policy(mut self, policy: UnknownOpcodePolicy) -> Self{
+        self.unknown_opcode_policy = policy;
+        self
+    }
+
+    /// 执行前把地址标记为 warm，用于复现依赖之前访问记录的 gas 计算
+    pub fn warm_address(mut self, address: Address) -> Self{
+        self.accessed_addresses.insert(address);
+        self
+    }
+
+    /// 执行前把 address 的存储槽 key 标记为 warm，之后首次 SLOAD/SSTORE 只收 warm 费用
+    pub fn warm_slot(mut self, address: Address, key: U256) -> Self{
+        self.accessed_storage_keys.insert((address, key));
+        self
+    }
+
+    // 弹出栈顶作为地址：取低 20 字节，严格模式下高位非 0 时报错
+    fn pop_address(&mut self) -> Result<Address, EvmError>{
+        let value = self.pop()?;
+        if self.strict_addresses && value.bits() > 160{
+            return Err(EvmError::DirtyAddress(value));
+        }
+        let mut buf = [0u8; 32];
+        value.to_big_endian(&mut buf);
+        Ok(Address::from_slice(&buf[12..32]))
+    }
+
+    // 所有修改状态的指令执行前调用，静态上下文中返回 StaticStateChange
+    fn require_non_static(&self) -> Result<(), EvmError>{
+        if self.is_static{
+            return Err(EvmError::StaticStateChange);
+        }
+        Ok(())
+    }
+
+    // 弹出 RETURN/REVERT 的数据长度并检查是否超过上限，超出 usize 的长度同样视为超过上限
+    fn pop_return_data_size(&mut self) -> Result<usize, EvmError>{
+        let length = self.pop()?;
+        let size = Self::u256_to_usize_saturating(length);
+        if size > self.max_return_data_size{
+            return Err(EvmError::ReturnDataTooLarge { size, limit: self.max_return_data_size });
+        }
+        Ok(size)
+    }
+
+    /// 辅助函数：将大端序字节切片转为 EVM 标准 U256（32 字节整数）
+    /// EVM 要求整数是 32 字节大端序，不足 32 字节时前面补 0
+    fn bytes_to_u256(data: &[u8])-> U256 {
+        if data.len()>=32{
+            U256::from_big_endian(&data[data.len() - 32..])
+        }else{
+            let mut buf = [0u8; 32];
+            let offset = 32 - data.len(); // 计算需要补几个0
+            if offset < 32{
+                buf[offset..].copy_from_slice(data);
+            }
+            U256::from_big_endian(&buf)
+        } 
+    }
+
+    // 读取当前 pc 处的指令，不移动 pc
+    fn current_instruction(&self) -> Option<u8>{
+        self.code.get(self.pc).copied()
+    }
+
+    pub fn pc(&self) -> usize{
+        self.pc
+    }
+
+    /// 当前堆栈（栈底——>栈顶）
+    pub fn stack(&self) -> &[U256]{
+        &self.stack
+    }
+
+    pub fn memory(&self) -> &[u8]{
+        &self.memory
+    }
+
+    pub fn gas_remaining(&self) -> u64{
+        self.gas_remaining
+    }
+
+    fn underflow_judge(&mut self, count: usize) -> Result<(), EvmError>{
+        if self.stack.len() < count{
+            return Err(EvmError::StackUnderflow { required: count, actual: self.stack.len() });
+        }
+        Ok(())
+    }
+
+    // 扣除 gas，剩余 gas 不足时终止执行
+    fn use_gas(&mut self, amount: u64, category: GasCategory) -> Result<(), EvmError>{
+        if self.gas_remaining < amount{
+            return Err(EvmError::OutOfGas { required: amount, remaining: self.gas_remaining });
+        }
+        self.gas_remaining -= amount;
+        self.gas_ledger.record(category, amount);
+        Ok(())
+    }
+
+    // EIP-2929：首次访问某地址（cold）需额外支付 2500，之后访问（warm）只收固定的 100
+    fn access_address(&mut self, addr: Address) -> Result<(), EvmError>{
+        if self.accessed_addresses.insert(addr){
+            self.use_gas(GAS_COLD_ACCOUNT_ACCESS - GAS_WARM_ACCESS, GasCategory::AccountAccess)?;
+        }
+        Ok(())
+    }
+
+    // EIP-2929：首次访问当前合约的某个存储槽（cold）需额外支付 2000，SLOAD 的固定费用已含 warm 的 100
+    fn access_slot(&mut self, key: U256) -> Result<(), EvmError>{
+        if self.accessed_storage_keys.insert((self.address, key)){
+            self.use_gas(GAS_COLD_SLOAD - GAS_WARM_ACCESS, GasCategory::Storage)?;
+        }
+        Ok(())
+    }
+
+    /// 按分类统计的 gas 消耗
+    pub fn gas_ledger(&self) -> GasLedger{
+        self.gas_ledger
+    }
+
+    /// 剩余 gas 占初始 gas 上限的百分比
+    pub fn gas_remaining_percent(&self) -> f64{
+        if self.gas_limit == 0{
+            return 0.0;
+        }
+        self.gas_remaining as f64 / self.gas_limit as f64 * 100.0
+    }
+
+    pub fn gas_used(&self) -> u64{
+        self.gas_limit - self.gas_remaining
+    }
+
+    /// CALL 系列指令实际转发给被调用者的 gas（EIP-150）
+    /// 最多只能转发剩余 gas 的 63/64；请求值超过 u64 时按饱和处理，
+    /// 所以 `PUSH32 0xff..ff` 表示“转发全部可用 gas”，而不会在转换时 panic
+    pub fn call_gas(&self, requested: U256) -> u64{
+        let requested = Self::u256_to_u64_saturating(requested);
+        let cap = self.gas_remaining - self.gas_remaining / 64;
+        requested.min(cap)
+    }
+
+    // U256 转 usize，超出 usize 范围时取 usize::MAX
+    fn u256_to_usize_saturating(value: U256) -> usize{
+        if value > U256::from(usize::MAX){
+            usize::MAX
+        }else{
+            value.as_usize()
+        }
+    }
+
+    // U256 转 u64，超出 u64 范围时取 u64::MAX
+    fn u256_to_u64_saturating(value: U256) -> u64{
+        if value > U256::from(u64::MAX){
+            u64::MAX
+        }else{
+            value.low_u64()
+        }
+    }
+
+    /// 交易执行完后结算手续费：gas_used * basefee 直接销毁，不记入任何账户；
+    /// gas_used * priority_fee_per_gas 作为小费记入 coinbase 的余额
+    pub fn settle_fees(&mut self, priority_fee_per_gas: U256) -> FeeSplit{
+        self.settle_fees_for(self.gas_used(), priority_fee_per_gas)
+    }
+
+    // 按指定的 gas_used 结算手续费；异常终止的交易按整个 gas 上限结算
+    pub(crate) fn settle_fees_for(&mut self, gas_used: u64, priority_fee_per_gas: U256) -> FeeSplit{
+        let gas_used = U256::from(gas_used);
+        let base_fee_burned = gas_used.saturating_mul(self.block_env.basefee);
+        let priority_fee_paid = gas_used.saturating_mul(priority_fee_per_gas);
+        let coinbase = self.account_db.entry(self.block_env.coinbase).or_default();
+        coinbase.balance = coinbase.balance.saturating_add(priority_fee_paid);
+        FeeSplit { base_fee_burned, priority_fee_paid }
+    }
+
+    /// 查询账户余额，账户不存在时为 0
+    pub fn balance_of(&self, addr: Address) -> U256{
+        self.account_db.get(&addr).map_or(U256::zero(), |account| account.balance)
+    }
+
+    /// 直接设置账户余额，账户不存在时先创建，方便测试前给账户充值
+    pub fn set_balance(&mut self, addr: Address, value: U256){
+        self.account_db.entry(addr).or_default().balance = value;
+    }
+
+    /// 从 from 向 to 转账 amount，所有转移余额的指令都经过这里
+    /// 余额不足或收款方余额溢出时返回错误，两个账户都保持不变
+    pub fn transfer_value(&mut self, from: Address, to: Address, amount: U256) -> Result<(), EvmError>{
+        if amount.is_zero(){
+            return Ok(());
+        }
+        let from_balance = self.balance_of(from);
+        let new_from_balance = from_balance.checked_sub(amount)
+            .ok_or(EvmError::InsufficientBalance { required: amount, available: from_balance })?;
+        if from == to{
+            return Ok(());
+        }
+        let new_to_balance = self.balance_of(to).checked_add(amount).ok_or(EvmError::BalanceOverflow)?;
+        self.set_balance(from, new_from_balance);
+        self.set_balance(to, new_to_balance);
+        Ok(())
+    }
+
+    /// 直接设置账户代码，账户不存在时先创建，CALL 该地址时执行这段代码
+    pub fn set_code(&mut self, addr: Address, code: Vec<u8>){
+        self.account_db.entry(addr).or_default().code = code;
+    }
+
+    /// 查询账户的 nonce，账户不存在时为 0
+    pub fn nonce(&self, addr: Address) -> U256{
+        self.account_db.get(&addr).map_or(U256::zero(), |account| account.nonce)
+    }
+
+    /// CREATE 的地址冲突检查：目标地址已有非零 nonce 或非空代码时不能部署，
+    /// 此时 CREATE 应压入 0 并消耗本次尝试的 gas，已有合约保持不变
+    pub fn create_collision(&self, addr: Address) -> bool{
+        self.account_db.get(&addr)
+            .is_some_and(|account| !account.nonce.is_zero() || !account.code.is_empty())
+    }
+
+    /// 账户 nonce 加一，账户不存在时先创建空账户；CREATE 推导新合约地址时依赖它
+    pub fn increment_nonce(&mut self, addr: Address){
+        let account = self.account_db.entry(addr).or_default();
+        account.nonce = account.nonce.saturating_add(U256::one());
+    }
+
+    fn push(&mut self, size: usize) -> Result<(), EvmError>{
+        if self.pc + size > self.code.len(){
+            return Err(EvmError::IncompletePush { required: size, remaining: self.code.len() - self.pc });
+        }
+        let data = &self.code[self.pc..self.pc + size];
+        println!("  {}", format_push(data));
+        let value = Self::bytes_to_u256(data);
+        self.stack.push(value);
+        self.pc += size;
+        Ok(())
+    }
+
+    // 弹出栈顶元素并转为 usize（内存偏移量、长度），超出 usize 范围时返回 MemoryOverflow
+    fn pop_usize(&mut self) -> Result<usize, EvmError>{
+        let value = self.pop()?;
+        if value > U256::from(usize::MAX){
+            return Err(EvmError::MemoryOverflow);
+        }
+        Ok(value.as_usize())
+    }
+
+    fn pop(&mut self) -> Result<U256, EvmError>{
+        self.underflow_judge(1)?;
+        Ok(self.stack.pop().unwrap())
+    }
+
+    /// 弹出栈顶两个元素，将相加结果push入栈
+    fn add(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        let (result,_) = a.overflowing_add(b);
+        self.stack.push(result);
+        Ok(())
+    }
+
+    /// 弹出栈顶两个元素，将元素2-元素1结果 push入栈
+    fn sub(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        let (result,_) = b.overflowing_sub(a);
+        self.stack.push(result);
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，将两元素相乘结果 push入栈
+    fn mul(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        let (result,_) = a.overflowing_mul(b);
+        self.stack.push(result);
+        Ok(())
+    }
+
+    // 除数为 0 时的处理：规范行为是结果为 0，学习模式下报错
+    fn zero_divisor(&mut self) -> Result<(), EvmError>{
+        if self.trap_div_by_zero{
+            return Err(EvmError::DivisionByZero);
+        }
+        self.stack.push(U256::zero());
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，将元素2/元素1结果 push入栈
+    fn div(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if a.is_zero(){
+            return self.zero_divisor();
+        }
+        self.stack.push(b / a);
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，将元素2%元素1结果 push入栈
+    fn modfn(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if a.is_zero(){
+            return self.zero_divisor();
+        }
+        self.stack.push(b % a);
+        Ok(())
+    }
+
+    // 有符号除法：按二进制补码解释，元素2/元素1，结果向 0 取整
+    fn sdiv(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if a.is_zero(){
+            return self.zero_divisor();
+        }
+        let quotient = abs_value(b) / abs_value(a);
+        // 商的符号由两个操作数的符号决定；MIN / -1 溢出后仍为 MIN
+        let result = if is_negative(a) != is_negative(b){ negate(quotient) }else{ quotient };
+        self.stack.push(result);
+        Ok(())
+    }
+
+    // 有符号取模：结果的符号与被除数（元素2）一致
+    fn smod(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if a.is_zero(){
+            return self.zero_divisor();
+        }
+        let remainder = abs_value(b) % abs_value(a);
+        let result = if is_negative(b){ negate(remainder) }else{ remainder };
+        self.stack.push(result);
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，元素2<元素1，push1，否则push0
+    fn lt(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if b < a{
+            self.stack.push(U256::one());
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，元素2 > 元素1，push1，否则push0
+    fn gt(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if b > a{
+            self.stack.push(U256::one());
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+    // 弹出栈顶两个元素，元素2 == 元素1，push1，否则push0
+    fn eq(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        if a==b {
+            self.stack.push(U256::one());
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    fn and(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        self.stack.push(b & a);
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let a = self.pop()?;
+        let b = self.pop()?;
+        self.stack.push(b | a);
+        Ok(())
+    }
+
+    fn not(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let a = self.pop()?;
+        self.stack.push(!a);
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，元素1为offset，元素2为value，往memory写入32字节的value
+    fn mstore(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        // offset 超出 usize 或 offset+32 溢出时返回 MemoryOverflow，超过内存上限同样报错
+        let offset = self.pop_usize()?;
+        let value = self.pop()?;
+        self.ensure_memory(offset, 32)?;
+        let mut buf = [0u8; 32];
+        value.to_big_endian(&mut buf); // 把整数转为大端序字节数组
+        self.memory[offset..offset + 32].copy_from_slice(&buf);
+        self.record_memory_write(offset, 32);
+        Ok(())
+    }
+
+    // 弹出栈顶两个元素，元素1为offset，元素2为value，往memory写入1字节的value
+    fn mstore8(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let offset = self.pop_usize()?;
+        let value = self.pop()?;
+        self.ensure_memory(offset, 1)?;
+        let byte_value = (value.low_u64() & 0xFF) as u8;
+        self.memory[offset] = byte_value;
+        self.record_memory_write(offset, 1);
+        Ok(())
+    }
+
+    // 弹出栈顶一个元素作为offset，从内存offset的位置加载32字节，再push入栈
+    fn mload(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let offset = self.pop_usize()?;
+        // 读取超出当前内存的部分时内存同样会扩展（补 0）
+        self.ensure_memory(offset, 32)?;
+        self.record_memory_read(offset, 32);
+        let value = U256::from_big_endian(&self.memory[offset..offset + 32]);
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// 确保内存覆盖 [offset, offset+len)：检查溢出与内存上限，按 32 字节字对齐扩展并收取内存扩展费用
+    /// len 为 0 时不访问内存，也不扩展
+    fn ensure_memory(&mut self, offset: usize, len: usize) -> Result<(), EvmError>{
+        if len == 0{
+            return Ok(());
+        }
+        let end = offset.checked_add(len).ok_or(EvmError::MemoryOverflow)?;
+        if end <= self.memory.len(){
+            return Ok(());
+        }
+        let new_size = end.checked_next_multiple_of(32).ok_or(EvmError::MemoryOverflow)?;
+        // 先检查上限再扩展，避免超大长度直接触发巨量内存分配
+        if new_size > self.max_memory_size{
+            return Err(EvmError::MemoryOverflow);
+        }
+        // 费用超出 u64 时按 gas 不足处理
+        let new_cost = gas::checked_memory_cost(new_size)
+            .ok_or(EvmError::OutOfGas { required: u64::MAX, remaining: self.gas_remaining })?;
+        let cost = new_cost - gas::memory_cost(self.memory.len());
+        self.use_gas(cost, GasCategory::Memory)?;
+        self.memory.resize(new_size, 0);
+        Ok(())
+    }
+
+    // 读取 [offset, offset+len) 的内存，len 为 0 时返回空切片（调用前需先 ensure_memory）
+    fn memory_slice(&self, offset: usize, len: usize) -> &[u8]{
+        if len == 0{
+            return &[];
+        }
+        &self.memory[offset..offset + len]
+    }
+
+    // 记录一次内存写入，指令执行完后通过 Tracer::on_memory_write 回调
+    fn record_memory_write(&mut self, offset: usize, len: usize){
+        let bytes = self.memory[offset..offset + len].to_vec();
+        self.memory_accesses.push(MemoryAccess::Write { offset, bytes });
+    }
+
+    // 记录一次内存读取，指令执行完后通过 Tracer::on_memory_read 回调
+    fn record_memory_read(&mut self, offset: usize, len: usize){
+        self.memory_accesses.push(MemoryAccess::Read { offset, len });
+    }
+
+    // 将内存长度push入栈
+    fn msize(&mut self){
+        self.stack.push(U256::from(self.memory.len()));
+    }
+
+    // 从堆栈弹出两个元素，元素1为key，元素2为value，放入Storage
+    fn sstore(&mut self) -> Result<(), EvmError>{
+        self.require_non_static()?;
+        self.underflow_judge(2)?;
+        let key = self.pop()?;
+        let value = self.pop()?;
+        // SSTORE 没有固定费用，cold 槽位要支付完整的 2100
+        if self.accessed_storage_keys.insert((self.address, key)){
+            self.use_gas(GAS_COLD_SLOAD, GasCategory::Storage)?;
+        }
+        // 槽位从 0 写成非 0 最贵，其余情况按重置计费（简化模型，不含退款）
+        let current = self.storage.get(&key).copied().unwrap_or_default();
+        let cost = if current.is_zero() && !value.is_zero(){ GAS_SSTORE_SET }else{ GAS_SSTORE_RESET };
+        self.use_gas(cost, GasCategory::Storage)?;
+        self.storage.insert(key,value);
+        Ok(())
+    }
+
+    // 从堆栈弹出一个元素作为key去查询Storage，将value push入栈
+    fn sload(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let key = self.pop()?;
+        self.access_slot(key)?;
+        if let Some(value) = self.storage.get(&key){
+            self.stack.push(*value);
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    /// 直接设置任意账户的存储槽，方便测试前准备合约状态
+    pub fn set_storage(&mut self, addr: Address, key: U256, value: U256){
+        if addr == self.address{
+            self.storage.insert(key, value);
+        }else{
+            self.account_db.entry(addr).or_default().storage.insert(key, value);
+        }
+    }
+
+    /// 按 key 升序返回所有非零的存储槽
+    pub fn storage_entries_sorted(&self) -> Vec<(U256, U256)>{
+        let mut entries: Vec<(U256, U256)> = self.storage.iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(key, value)| (*key, *value))
+            .collect();
+        entries.sort_by_key(|(key, _)| *key);
+        entries
+    }
+
+    /// 查询任意账户的存储槽，当前合约读取正在执行的存储，其余账户读取账户数据库
+    pub fn storage_of(&self, addr: Address, key: U256) -> U256{
+        let storage = if addr == self.address{
+            &self.storage
+        }else{
+            match self.account_db.get(&addr){
+                Some(account) => &account.storage,
+                None => return U256::zero(),
+            }
+        };
+        storage.get(&key).copied().unwrap_or_default()
+    }
+
+    /// 分页返回排序后的存储槽，跳过前 offset 条，最多返回 limit 条
+    pub fn storage_entries_page(&self, offset: usize, limit: usize) -> Vec<(U256, U256)>{
+        self.storage_entries_sorted().into_iter().skip(offset).take(limit).collect()
+    }
+
+    /// 按产生顺序返回所有日志
+    pub fn logs(&self) -> &[Log]{
+        &self.logs
+    }
+
+    /// 包含 topic（任意位置）的日志
+    pub fn logs_with_topic(&self, topic: H256) -> Vec<&Log>{
+        self.logs.iter().filter(|log| log.topics.contains(&topic)).collect()
+    }
+
+    /// 由 address 合约产生的日志
+    pub fn logs_from(&self, address: Address) -> Vec<&Log>{
+        self.logs.iter().filter(|log| log.address == address).collect()
+    }
+
+    fn jump(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        // 超出 usize 的目标一定不是合法的 JUMPDEST
+        let destination = Self::u256_to_usize_saturating(self.pop()?);
+        if self.jump_destinations.contains(destination){
+            self.pc = destination;
+        }else{
+            return Err(EvmError::InvalidJump(destination));
+        }
+        Ok(())
+    }
+
+    fn jump_i(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        // 超出 usize 的目标一定不是合法的 JUMPDEST
+        let destination = Self::u256_to_usize_saturating(self.pop()?);
+        let condition = self.pop()?;
+        if !condition.is_zero(){
+            if self.jump_destinations.contains(destination){
+                self.pc = destination;
+            }else{
+                return Err(EvmError::InvalidJump(destination));
+            }
+        }
+        Ok(())
+    }
+
+    fn pcfn(&mut self) {
+        self.stack.push(U256::from(self.pc));
+    }
+
+    // 查询特定区块的hash
+    fn blockhash(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let number =  self.pop()?;
+        if number == self.block_env.number{
+            self.stack.push(U256::from_big_endian(self.block_env.blockhash.as_bytes()));
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    fn coinbase(&mut self){
+        self.stack.push(U256::from_big_endian(self.block_env.coinbase.as_bytes()));
+    }
+
+    fn timestamp(&mut self){
+        self.stack.push(self.block_env.timestamp);
+    }
+
+    // 将当前区块高度压入堆栈
+    fn number(&mut self){
+        self.stack.push(self.block_env.number);
+    }
+
+    // 获取上一个区块的随机数输出
+    fn prevrandao(&mut self){
+        self.stack.push(U256::from_big_endian(self.block_env.prevrandao.as_bytes()));
+    }
+
+    fn gaslimit(&mut self){
+        self.stack.push(self.block_env.gaslimit);
+    }
+
+    fn chainid(&mut self){
+        self.stack.push(self.block_env.chainid);
+    }
+
+    // 当前执行合约的地址，按低 20 字节压入
+    fn addressfn(&mut self){
+        self.stack.push(U256::from_big_endian(self.address.as_bytes()));
+    }
+
+    // 当前执行合约自身的余额，从账户信息中读取
+    fn selfbalance(&mut self){
+        let balance = self.balance_of(self.address);
+        self.stack.push(balance);
+    }
+
+    fn basefee(&mut self){
+        self.stack.push(self.block_env.basefee);
+    }
+
+    fn blobbasefee(&mut self){
+        self.stack.push(self.block_env.blobbasefee);
+    }
+
+    // 复制从栈顶数第 position 个元素（DUP1 复制栈顶）
+    fn dup(&mut self, position: usize) -> Result<(), EvmError>{
+        // DUP 至少复制栈顶（position 为 1），position 为 0 时按下溢处理而不是 panic
+        if position == 0 {
+            return Err(EvmError::StackUnderflow { required: 1, actual: self.stack.len() });
+        }
+
+        // 用 checked_sub 计算下标，元素不足时返回下溢错误，减法本身不会溢出
+        let index = self.stack.len().checked_sub(position)
+            .ok_or(EvmError::StackUnderflow { required: position, actual: self.stack.len() })?;
+        let value = self.stack[index];
+        self.stack.push(value);
+        Ok(())
+    }
+
+    // 交换栈顶与从栈顶数第 position+1 个元素（SWAP16 需要 17 个元素）
+    fn swap(&mut self, position: usize) -> Result<(), EvmError>{
+        let required = position + 1;
+        let stack_len = self.stack.len();
+        let idx2 = stack_len.checked_sub(required)
+            .ok_or(EvmError::StackUnderflow { required, actual: stack_len })?;
+        let idx1 = stack_len - 1;
+        self.stack.swap(idx1, idx2);
+        Ok(())
+    }
+
+    fn sha3(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let memory_offset = self.pop_usize()?;
+        let size = self.pop_usize()?;
+        // 先扩展内存（检查内存上限，超出时在扩展和哈希之前返回 MemoryOverflow），再按字数收费；
+        // 长度为 0 时哈希空数据，不扩展内存
+        self.ensure_memory(memory_offset, size)?;
+        self.use_gas(GAS_SHA3_WORD * size.div_ceil(32) as u64, GasCategory::Computation)?;
+        if size > 0{
+            self.record_memory_read(memory_offset, size);
+        }
+        let data = self.memory_slice(memory_offset, size);
+        let mut hasher = Keccak256::new();
+        hasher.update(data);
+        let result = hasher.finalize();
+        let hash_value = U256::from_big_endian(&result);
+        self.stack.push(hash_value);
+        Ok(())
+    }
+
+    fn balance(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let addr = self.pop_address()?;
+        self.access_address(addr)?;
+        if  let Some(account) = self.account_db.get(&addr){
+            self.stack.push(account.balance);
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    fn extcodesize(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let addr = self.pop_address()?;
+        self.access_address(addr)?;
+        if  let Some(account) = self.account_db.get(&addr){
+            self.stack.push(U256::from(account.code.len() as u64));
+        }else{
+            self.stack.push(U256::zero());
+        }
+        Ok(())
+    }
+
+    fn extcodecopy(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(4)?;
+
+        let addr = self.pop_address()?;
+        self.access_address(addr)?;
+
+        let mem_offset = self.pop_usize()?;
+        let code_offset = self.pop()?;
+        let length = self.pop_usize()?;
+
+        if length==0{
+            return Ok(());
+        }
+        self.ensure_memory(mem_offset, length)?;
+
+        let code_slice: &[u8] = if let Some(account)=self.account_db.get(&addr){
+            &account.code
+        }else{
+            &[]
+        };
+
+        // 超出代码长度的部分补 0
+        let dest = &mut self.memory[mem_offset..mem_offset + length];
+        dest.fill(0);
+        if code_offset < U256::from(code_slice.len()){
+            let code_offset = code_offset.as_usize();
+            let to_copy_len = std::cmp::min(code_slice.len() - code_offset, length);
+            dest[..to_copy_len].copy_from_slice(&code_slice[code_offset..code_offset + to_copy_len]);
+        }
+        self.record_memory_write(mem_offset, length);
+        Ok(())
+    }
+
+    fn extcodehash(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let addr = self.pop_address()?;
+        self.access_address(addr)?;
+
+        if let Some(account)=self.account_db.get(&addr){
+            let code: &[u8] = &account.code;
+            let mut hasher = Keccak256::new();
+            hasher.update(code);
+            let result = hasher.finalize();
+            let result_value = U256::from_big_endian(&result);
+            self.stack.push(result_value);
+        }else{
+            self.stack.push(U256::zero());
+        };
+        Ok(())
+    }
+
+    fn logn(&mut self, num_topics:usize) -> Result<(), EvmError>{
+        self.require_non_static()?;
+        self.underflow_judge(num_topics + 2)?;
+        let memory_offset = self.pop_usize()?;
+        let length = self.pop_usize()?;
+        let mut topics = Vec::with_capacity(num_topics);
+        for _ in 0..num_topics{
+            let topic = self.pop()?;
+            let mut buf = [0u8;32];
+            topic.to_big_endian(&mut buf);
+            topics.push(H256::from(buf));
+        }
+        // gas = 375 + 375 * topic数量 + 8 * 数据字节数
+        // 超大长度按饱和计算，gas 不足时报 OutOfGas 而不是溢出
+        let cost = (GAS_LOG + GAS_LOG_TOPIC * num_topics as u64)
+            .saturating_add(GAS_LOG_DATA.saturating_mul(length as u64));
+        self.use_gas(cost, GasCategory::Logs)?;
+        // 日志数据超出当前内存时和 MSTORE 一样扩展内存（补 0）并收取扩展费用
+        self.ensure_memory(memory_offset, length)?;
+        self.record_memory_read(memory_offset, length);
+        let data = self.memory_slice(memory_offset, length);
+        let log_entry=Log{
+            address: self.address,
+            data: data.to_vec(),
+            topics,
+        };
+        self.logs.push(log_entry);
+        Ok(())
+    }
+
+    fn returnfn(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let memory_offset = self.pop_usize()?;
+        let length = self.pop_return_data_size()?;
+        self.ensure_memory(memory_offset, length)?;
+        self.record_memory_read(memory_offset, length);
+        self.output = self.memory_slice(memory_offset, length).to_vec();
+        Ok(())
+    }
+
+    fn return_data_size(&mut self){
+        self.stack.push(U256::from(self.return_data.len()));
+    }
+
+    fn callerfn(&mut self){
+        self.stack.push(U256::from_big_endian(self.caller.as_bytes()));
+    }
+
+    fn callvalue(&mut self){
+        self.stack.push(self.value);
+    }
+
+    fn gasprice(&mut self){
+        self.stack.push(self.gas_price);
+    }
+
+    // 从 calldata 的 offset 处读取 32 字节，超出 calldata 的部分补 0
+    fn calldataload(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(1)?;
+        let offset = self.pop()?;
+        let mut word = [0u8; 32];
+        if offset < U256::from(self.calldata.len()){
+            let offset = offset.as_usize();
+            let len = (self.calldata.len() - offset).min(32);
+            word[..len].copy_from_slice(&self.calldata[offset..offset + len]);
+        }
+        self.stack.push(U256::from_big_endian(&word));
+        Ok(())
+    }
+
+    fn calldatasize(&mut self){
+        self.stack.push(U256::from(self.calldata.len()));
+    }
+
+    // 弹出 memOffset、dataOffset、length，把 calldata 复制到内存，超出 calldata 的部分补 0
+    fn calldatacopy(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(3)?;
+        let memory_offset = self.pop_usize()?;
+        let data_offset = self.pop()?;
+        let length = self.pop_usize()?;
+        if length == 0{
+            return Ok(());
+        }
+        self.ensure_memory(memory_offset, length)?;
+        let dest = &mut self.memory[memory_offset..memory_offset + length];
+        dest.fill(0);
+        if data_offset < U256::from(self.calldata.len()){
+            let data_offset = data_offset.as_usize();
+            let to_copy_len = (self.calldata.len() - data_offset).min(length);
+            dest[..to_copy_len].copy_from_slice(&self.calldata[data_offset..data_offset + to_copy_len]);
+        }
+        self.record_memory_write(memory_offset, length);
+        Ok(())
+    }
+
+    fn return_data_copy(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(3)?;
+        let memory_offset = self.pop_usize()?;
+        // 超出 usize 的 dataOffset/length 一定超出返回数据，在下面报 ReturnDataOutOfBounds
+        let data_offset = Self::u256_to_usize_saturating(self.pop()?);
+        let length = Self::u256_to_usize_saturating(self.pop()?);
+        // 读取范围超出返回数据时报错
+        let data_end = data_offset.checked_add(length).ok_or(EvmError::ReturnDataOutOfBounds)?;
+        if data_end>self.return_data.len(){
+            return Err(EvmError::ReturnDataOutOfBounds);
+        }
+        if length==0{
+            return Ok(());
+        }
+        self.ensure_memory(memory_offset, length)?;
+        self.memory[memory_offset..memory_offset + length].copy_from_slice(&self.return_data[data_offset..data_end]);
+        self.record_memory_write(memory_offset, length);
+        Ok(())
+    }
+
+    fn revert(&mut self) -> Result<(), EvmError>{
+        self.underflow_judge(2)?;
+        let memory_offset = self.pop_usize()?;
+        let length = self.pop_return_data_size()?;
+        self.ensure_memory(memory_offset, length)?;
+        self.record_memory_read(memory_offset, length);
+        self.output = self.memory_slice(memory_offset, length).to_vec();
+        self.success = false;
+        self.reverted = true;
+        Ok(())
+    }
+
+    fn invalid(&mut self){
+        self.success = false;
+    }
+
+    // 按配置的策略处理未知 opcode
+    fn unknown_opcode(&mut self, op: u8) -> Result<(), EvmError>{
+        match self.unknown_opcode_policy{
+            UnknownOpcodePolicy::Halt => {
+                println!("不支持的opcode：0x{:02x}，停止执行并消耗全部 gas", op);
+                self.use_gas(self.gas_remaining, GasCategory::Computation)?;
+                self.success = false;
+                Ok(())
+            }
+            UnknownOpcodePolicy::Error => Err(EvmError::InvalidOpcode(op)),
+            UnknownOpcodePolicy::Skip => {
+                println!("不支持的opcode：0x{:02x}，跳过", op);
+                Ok(())
+            }
+        }
+    }
+
+    /// 当前 pc 附近 ±3 条指令的反汇编列表，`=>` 标记停止位置的指令
+    /// 执行结束后 pc 已越过最后执行的指令，所以停止位置取 pc 之前的那条
+    pub fn context_listing(&self) -> String{
+        let instructions = asm::disassemble(&self.code);
+        if instructions.is_empty(){
+            return String::new();
+        }
+        let target = self.pc.saturating_sub(1);
+        let current = instructions.iter()
+            .rposition(|ins| ins.pc <= target)
+            .unwrap_or(0);
+        let start = current.saturating_sub(3);
+        let end = (current + 4).min(instructions.len());
+
+        let mut listing = String::new();
+        for (i, ins) in instructions[start..end].iter().enumerate(){
+            let marker = if start + i == current { "=>" } else { "  " };
+            listing.push_str(&format!("{} {:>4}: {}\n", marker, ins.pc, ins));
+        }
+        listing
+    }
+
+    /// 汇总当前状态为执行结果，error 为 run 返回的错误
+    pub fn execution_result(&self, error: Option<EvmError>) -> ExecutionResult{
+        ExecutionResult {
+            success: self.success && error.is_none(),
+            stack: self.stack.clone(),
+            return_data: self.output.clone(),
+            gas_used: self.gas_used(),
+            gas_ledger: self.gas_ledger,
+            error,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<(), EvmError>{
+        self.run_with_tracer(&mut NoopTracer)
+    }
+
+    /// 执行字节码，每条指令执行前后回调 tracer
+    pub fn run_with_tracer(&mut self, tracer: &mut dyn Tracer) -> Result<(), EvmError>{
+        println!("开始执行字节码，初始pc: {}", self.pc);
+        if self.code.is_empty(){
+            println!("字节码为空，隐式停止");
+        }
+        let started = Instant::now();
+        let mut steps: u64 = 0;
+        while !self.is_halted(){
+            steps += 1;
+            if let Some(limit) = self.max_duration
+                && steps.is_multiple_of(TIMEOUT_CHECK_INTERVAL)
+                && started.elapsed() > limit
+            {
+                return Err(EvmError::Timeout { limit });
+            }
+            self.execute_instruction(tracer)?;
+        }
+        println!("字节码执行完毕！");
+        Ok(())
+    }
+
+    /// 执行已经停止：遇到 STOP/RETURN、执行失败（REVERT 等），或 pc 越过代码末尾
+    pub fn is_halted(&self) -> bool{
+        self.halted || !self.success || self.pc >= self.code.len()
+    }
+
+    // 执行 pc 处的一条指令，pc 已越过代码末尾时什么都不做
+    // 指令出错时执行失败并停止，之后再调用 step/run 不会从出错的指令之后继续执行
+    fn execute_instruction(&mut self, tracer: &mut dyn Tracer) -> Result<(), EvmError>{
+        let result = self.dispatch_instruction(tracer);
+        if result.is_err(){
+            self.success = false;
+            self.memory_accesses.clear();
+        }
+        result
+    }
+
+    fn dispatch_instruction(&mut self, tracer: &mut dyn Tracer) -> Result<(), EvmError>{
+        let Some(op) = self.current_instruction() else { return Ok(()) };
+        tracer.before_op(self, op);
+        self.pc += 1;
+        println!("当前opcode为：0x{:02x}", op);
+        self.use_gas(gas::static_gas(op), gas::category(op))?;
+        match op{
+            STOP => {
+                println!("程序终止");
+                self.halted = true;
+            }
+            PUSH1..=PUSH32 => {
+                let size = ((op-PUSH1) + 1) as usize;
+                self.push(size)?;
+            }
+            PUSH0 => {
+                println!("  识别PUSH0指令，压入0");
+                self.stack.push(U256::zero());
+            }
+            POP => {
+                println!("  识别POP指令");
+                self.pop()?;
+            }
+            ADD => {
+                println!("  识别ADD指令");
+                self.add()?;
+            }
+            SUB => {
+                println!("  识别SUB指令");
+                self.sub()?;
+            }
+            MUL => {
+                println!("  识别MUL指令");
+                self.mul()?;
+            }
+            DIV => {
+                println!("  识别DIV指令");
+                self.div()?;
+            }
+            SDIV => {
+                println!("  识别SDIV指令");
+                self.sdiv()?;
+            }
+            MOD => {
+                println!("  识别MOD指令");
+                self.modfn()?;
+            }
+            SMOD => {
+                println!("  识别SMOD指令");
+                self.smod()?;
+            }
+            LT => {
+                println!("  识别LT指令");
+                self.lt()?;
+            }
+            GT => {
+                println!("  识别GT指令");
+                self.gt()?;
+            }
+            EQ => {
+                println!("  识别EQ指令");
+                self.eq()?;
+            }
+            AND => { // 新增：与指令
+                println!("  识别AND指令");
+                self.and()?;
+            }
+            OR => {
+                println!("  识别OR指令");
+                self.or()?;
+            }
+            NOT => {
+                println!("  识别NOT指令");
+                self.not()?;
+            }
+            MSTORE => { 
+                println!("  识别MSTORE指令");
+                self.mstore()?;
+            }
+            MSTORE8 => { 
+                println!("  识别MSTORE8指令");
+                self.mstore8()?;
+            }
+            MLOAD => { 
+                println!("  识别MLOAD指令");
+                self.mload()?;
+            }
+            MSIZE => { 
+                println!("  识别MSIZE指令");
+                self.msize();
+            }
+            SSTORE => {
+                println!("  识别SSTORE指令");
+                self.sstore()?;
+            }
+            SLOAD => {
+                println!("  识别SLOAD指令");
+                self.sload()?;
+            }
+            JUMPDEST => {
+                println!("  识别JUMPDEST指令");
+            }
+            JUMP => {
+                println!("  识别JUMP指令");
+                self.jump()?;
+            }
+            JUMPI => {
+                println!("  识别JUMPI指令");
+                self.jump_i()?;
+            }
+            PC => {
+                self.pcfn();
+            }
+            BLOCKHASH => {
+                println!("  识别BLOCKHASH指令");
+                self.blockhash()?;
+            }
+            COINBASE => {
+                println!("  识别COINBASE指令");
+                self.coinbase();
+            }
+            TIMESTAMP => {
+                println!("  识别TIMESTAMP指令");
+                self.timestamp();
+            }
+            NUMBER => {
+                println!("  识别NUMBER指令");
+                self.number();
+            }
+            PREVRANDAO => {
+                println!("  识别PREVRANDAO指令");
+                self.prevrandao();
+            }
+            GASLIMIT => {
+                println!("  识别GASLIMIT指令");
+                self.gaslimit();
+            }
+            CHAINID => {
+                println!("  识别CHAINID指令");
+                self.chainid();
+            }
+            SELFBALANCE => {
+                println!("  识别SELFBALANCE指令");
+                self.selfbalance();
+            }
+            BASEFEE => {
+                println!("  识别BASEFEE指令");
+                self.basefee();
+            }
+            BLOBBASEFEE => {
+                println!("  识别BLOBBASEFEE指令");
+                self.blobbasefee();
+            }
+            DUP1..=DUP16 => {
+                let position = (op - DUP1 + 1) as usize;
+                self.dup(position)?;
+            }
+            SWAP1..=SWAP16 => {
+                let position = (op - SWAP1 + 1) as usize;
+                self.swap(position)?;
+            }
+            SHA3 =>{
+                self.sha3()?;
+            }
+            ADDRESS =>{
+                println!("  识别ADDRESS指令");
+                self.addressfn();
+            }
+            CALLER =>{
+                println!("  识别CALLER指令");
+                self.callerfn();
+            }
+            CALLVALUE =>{
+                println!("  识别CALLVALUE指令");
+                self.callvalue();
+            }
+            CALLDATALOAD =>{
+                println!("  识别CALLDATALOAD指令");
+                self.calldataload()?;
+            }
+            CALLDATASIZE =>{
+                println!("  识别CALLDATASIZE指令");
+                self.calldatasize();
+            }
+            CALLDATACOPY =>{
+                println!("  识别CALLDATACOPY指令");
+                self.calldatacopy()?;
+            }
+            GASPRICE =>{
+                println!("  识别GASPRICE指令");
+                self.gasprice();
+            }
+            BALANCE =>{
+                self.balance()?;
+            }
+            EXTCODESIZE => {
+                self.extcodesize()?;
+            }
+            EXTCODECOPY => {
+                self.extcodecopy()?;
+            }
+            EXTCODEHASH => {
+                self.extcodehash()?;
+            }
+            LOG0..=LOG4 =>{
+                let num_topics = (op - LOG0) as usize;
+                self.logn(num_topics)?;
+            }
+            RETURN =>{
+                self.returnfn()?;
+                self.halted = true;
+            }
+            RETURNDATASIZE =>{
+                self.return_data_size();
+            }
+            RETURNDATACOPY =>{
+                self.return_data_copy()?;
+            }
+            CREATE => {
+                self.create()?;
+            }
+            CALL => {
+                self.call()?;
+            }
+            STATICCALL => {
+                self.static_call()?;
+            }
+            REVERT => {
+                self.revert()?;
+            }
+            INVALID =>{
+                self.invalid();
+            }
+            _ => self.unknown_opcode(op)?,
+        }
+        println!(
+            "  执行完毕后，pc:{}，堆栈长度：{}，剩余gas：{}（{:.2}%）",
+            self.pc, self.stack.len(), self.gas_remaining, self.gas_remaining_percent()
+        );
+        for access in self.memory_accesses.drain(..){
+            match access{
+                MemoryAccess::Read { offset, len } => tracer.on_memory_read(offset, len),
+                MemoryAccess::Write { offset, bytes } => tracer.on_memory_write(offset, &bytes),
+            }
+        }
+        tracer.after_op(self, op);
+        Ok(())
+    }
+}
+
+// 自定义堆栈输出格式
+impl fmt::Display for EVM {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        let opts = DisplayOptions::new().with_verbose(self.verbose_display);
+        self.write_state(f, &opts)
+    }
+}
+
+impl EVM{
+    /// 按显示选项输出最终状态，`Display` 等价于使用默认选项
+    pub fn fmt_with(&self, opts: &DisplayOptions) -> String{
+        let mut text = String::new();
+        // 写入 String 不会失败
+        let _ = self.write_state(&mut text, opts);
+        text
+    }
+
+    fn write_state(&self, f: &mut dyn fmt::Write, opts: &DisplayOptions) -> fmt::Result{
+        writeln!(f, "EVM 最终状态:")?;
+        writeln!(f,"    字节码长度：{}字节", self.code.len())?;
+        writeln!(f,"    程序计数器：{}",  self.pc)?;
+        writeln!(f,"    Gas消耗：{}/{}", self.gas_used(), self.gas_limit)?;
+        writeln!(f,"    停止位置附近的指令：")?;
+        for line in self.context_listing().lines(){
+            writeln!(f, "      {}", line)?;
+        }
+        writeln!(f,"    堆栈（栈底——>栈顶）：")?;
+        // 项数超出上限时只显示靠近栈顶的部分
+        let hidden = opts.max_stack_slots.map_or(0, |max| self.stack.len().saturating_sub(max));
+        if hidden > 0{
+            writeln!(f, "           （省略栈底 {} 项）", hidden)?;
+        }
+        for (i, val) in self.stack.iter().enumerate().skip(hidden){
+            writeln!(f, "           第 {} 位 {}", i, display::format_word_with(*val, opts))?;
+        }
+
+        writeln!(f, "   内存Memory：")?;
+        for line in display::format_memory(&self.memory, opts){
+            writeln!(f, "      {}", line)?;
+        }
+
+        writeln!(f, "   存储Storage：")?;
+        for (key, value) in self.storage_entries_sorted(){
+            writeln!(f, "      {}: {}", key, value)?;
+        }
+
+        writeln!(f, "   日志Logs:")?;
+        for (i, log) in self.logs.iter().enumerate() {
+            writeln!(
+                f,
+                "      Log {}: address={}, topics={:?}, data=0x{}",
+                i,
+                log.address,
+                log.topics,
+                hex::encode(&log.data)
+            )?;
+        }
+
+        writeln!(f, "   返回数据returnData：")?;
+        write!(f,"      ")?;
+        for val in self.output.iter(){
+            write!(f, "{:02x}",val)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 按二进制补码解释，最高位为 1 表示负数
+fn is_negative(value: U256) -> bool{
+    value.bit(255)
+}
+
+// 二进制补码取负
+fn negate(value: U256) -> U256{
+    (!value).overflowing_add(U256::one()).0
+}
+
+// 有符号数的绝对值
+fn abs_value(value: U256) -> U256{
+    if is_negative(value){ negate(value) }else{ value }
+}
+
+/// 格式化一个 32 字节的字（堆栈元素），默认显示十进制和十六进制；
+/// verbose 时额外显示按地址（低 20 字节）和 bytes32 解释的结果
+pub fn format_word(value: U256, verbose: bool) -> String{
+    display::format_word_with(value, &DisplayOptions::new().with_verbose(verbose))
+}
+
+/// 格式化 PUSHn 的跟踪行，同时给出立即数的十六进制和十进制，
+/// 例如 `PUSH2 0x0102` 显示为 `识别PUSH2指令，压入0x0102（十进制258）`
+pub fn format_push(immediate: &[u8]) -> String{
+    let value = U256::from_big_endian(immediate);
+    format!("识别PUSH{}指令，压入0x{}（十进制{}）", immediate.len(), hex::encode(immediate), value)
+}
+
+/// 计算函数选择器：keccak256(函数签名) 的前 4 字节，
+/// 例如 `selector("transfer(address,uint256)")` 返回 `[0xa9, 0x05, 0x9c, 0xbb]`
+pub fn selector(sig: &str) -> [u8; 4]{
+    let hash = Keccak256::digest(sig.as_bytes());
+    let mut out = [0u8; 4];
+    out.copy_from_slice(&hash[..4]);
+    out
+}
+
+/// 将十六进制字符串解析为字节码，允许带 0x 前缀和空白字符
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, EvmError> {
+    let cleaned: String = input.split_whitespace().collect();
+    let cleaned = cleaned.strip_prefix("0x").unwrap_or(&cleaned);
+    hex::decode(cleaned).map_err(|e| EvmError::InvalidHex(e.to_string()))
+}
+
+/// 一行执行十六进制字节码，返回栈顶元素，适合快速验证简单的算术程序
+/// 例如 `eval("6002600301")` 返回 5
+pub fn eval(hex: &str) -> Result<U256, EvmError> {
+    let code = decode_hex(hex)?;
+    let mut evm = EVM::new(code);
+    evm.run()?;
+    evm.stack.last().copied().ok_or(EvmError::StackUnderflow { required: 1, actual: 0 })
+}
+
+/// 与 `eval` 相同，但返回最终的整个堆栈（栈底——>栈顶）
+/// 例如 `eval_stack("600160026003")` 返回 [1, 2, 3]
+pub fn eval_stack(hex: &str) -> Result<Vec<U256>, EvmError> {
+    let code = decode_hex(hex)?;
+    let mut evm = EVM::new(code);
+    evm.run()?;
+    Ok(evm.stack)
+}
+
+/// 与 `eval` 相同，但返回 RETURN/REVERT 的数据，没有返回数据时为空
+pub fn eval_return(hex: &str) -> Result<Vec<u8>, EvmError> {
+    let code = decode_hex(hex)?;
+    let mut evm = EVM::new(code);
+    evm.run()?;
+    Ok(evm.output)
+}
+
+// 编译期保证 EVM 可以在线程间移动
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<EVM>();
+};
+
+/// 并行执行多段互不相关的字节码，结果顺序与输入一致
+/// 每段程序使用独立的 EVM 实例，线程之间没有共享的可变状态
+pub fn run_many(programs: Vec<Vec<u8>>) -> Vec<ExecutionResult> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = programs.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = programs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk.iter().map(|code| {
+                        let mut evm = EVM::new(code.clone());
+                        let error = evm.run().err();
+                        evm.execution_result(error)
+                    }).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_memory_grows_to_word_boundary_and_charges_gas() {
+        let mut evm = EVM::new(vec![]);
+        let gas = evm.gas_remaining();
+        evm.ensure_memory(10, 30).unwrap();
+        assert_eq!(evm.memory.len(), 64);
+        assert_eq!(gas - evm.gas_remaining(), gas::memory_cost(64));
+    }
+
+    #[test]
+    fn ensure_memory_is_noop_when_already_large_enough() {
+        let mut evm = EVM::new(vec![]).with_memory(vec![0; 64]);
+        let gas = evm.gas_remaining();
+        evm.ensure_memory(0, 64).unwrap();
+        evm.ensure_memory(100, 0).unwrap();
+        assert_eq!(evm.memory.len(), 64);
+        assert_eq!(evm.gas_remaining(), gas);
+    }
+
+    #[test]
+    fn ensure_memory_rejects_overflowing_range() {
+        let mut evm = EVM::new(vec![]);
+        assert_eq!(evm.ensure_memory(usize::MAX, 2), Err(EvmError::MemoryOverflow));
+        assert!(evm.memory.is_empty());
+    }
+
+    #[test]
+    fn ensure_memory_rejects_growth_past_cap() {
+        let mut evm = EVM::new(vec![]).with_max_memory_size(64);
+        evm.ensure_memory(0, 64).unwrap();
+        assert_eq!(evm.ensure_memory(0, 65), Err(EvmError::MemoryOverflow));
+        assert_eq!(evm.memory.len(), 64);
+    }
+
+    #[test]
+    fn dup_position_zero_is_an_error_not_a_panic() {
+        let mut evm = EVM::new(vec![]);
+        evm.stack.push(U256::one());
+        assert_eq!(evm.dup(0), Err(EvmError::StackUnderflow { required: 1, actual: 1 }));
+        assert_eq!(evm.stack, vec![U256::one()]);
+    }
+
+    #[test]
+    fn ensure_memory_reports_out_of_gas_when_the_cost_overflows() {
+        let mut evm = EVM::new(vec![]).with_max_memory_size(usize::MAX);
+        let gas = evm.gas_remaining();
+        assert_eq!(
+            evm.ensure_memory(0, usize::MAX - 31),
+            Err(EvmError::OutOfGas { required: u64::MAX, remaining: gas })
+        );
+        assert!(evm.memory.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+// 静态检查：不执行字节码，找出一定会发生的堆栈下溢
+//
+// 从 pc 0 开始按顺序累计每条指令对堆栈高度的影响（见 asm::stack_io）。
+// 跳转让分析变得不确定，所以只分析入口处的直线代码：
+//   遇到 JUMPDEST（可能从别处跳来，堆栈高度未知）、无条件终止指令（之后的代码只能通过跳转到达）
+//   或未知 opcode 时停止分析。JUMPI 不跳转时会继续执行，所以不会停止。
+
+use crate::asm::{disassemble, opcode_name, stack_io};
+use crate::*;
+
+/// 一定会发生的堆栈下溢
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackUnderflowLint {
+    pub pc: usize,
+    pub opcode: u8,
+    // 指令需要的元素个数和执行到这里时堆栈上的元素个数
+    pub required: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for StackUnderflowLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pc {} 处的 {} 需要{}个堆栈元素，此时只有{}个",
+            self.pc,
+            opcode_name(self.opcode),
+            self.required,
+            self.available
+        )
+    }
+}
+
+/// 返回入口直线代码中第一处一定会发生的堆栈下溢，没有发现时返回 None
+pub fn check_stack_underflow(code: &[u8]) -> Option<StackUnderflowLint> {
+    let mut height: usize = 0;
+    for ins in disassemble(code) {
+        if ins.opcode == JUMPDEST {
+            return None;
+        }
+        let (pops, pushes) = stack_io(ins.opcode)?;
+        if height < pops {
+            return Some(StackUnderflowLint { pc: ins.pc, opcode: ins.opcode, required: pops, available: height });
+        }
+        height = height - pops + pushes;
+        if matches!(ins.opcode, STOP | JUMP | RETURN | REVERT | INVALID) {
+            return None;
+        }
+    }
+    None
+}
@@ -0,0 +1,58 @@
+// 指令耗时分析：统计解释器在每种 opcode 上累计花费的真实时间
+//
+// 计时包括 tracer 回调之间的全部工作（执行指令、打印日志等），
+// 结果只适合在同一台机器上比较不同 opcode 的相对开销。
+
+use crate::asm::opcode_name;
+use crate::tracer::Tracer;
+use crate::EVM;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 按 opcode 累计执行耗时的 tracer
+#[derive(Debug, Default)]
+pub struct OpcodeProfiler {
+    // 当前指令开始执行的时刻
+    started: Option<Instant>,
+    timings: HashMap<u8, Duration>,
+}
+
+impl OpcodeProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 每种 opcode 累计的执行耗时
+    pub fn timings(&self) -> &HashMap<u8, Duration> {
+        &self.timings
+    }
+
+    /// 按耗时从高到低返回最多 n 个 opcode
+    pub fn hottest(&self, n: usize) -> Vec<(u8, Duration)> {
+        let mut entries: Vec<(u8, Duration)> = self.timings.iter().map(|(op, time)| (*op, *time)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+
+    /// 耗时最高的 n 个 opcode，每行一个
+    pub fn report(&self, n: usize) -> String {
+        self.hottest(n)
+            .iter()
+            .map(|(op, time)| format!("{:<16} {:?}", opcode_name(*op), time))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Tracer for OpcodeProfiler {
+    fn before_op(&mut self, _evm: &EVM, _op: u8) {
+        self.started = Some(Instant::now());
+    }
+
+    fn after_op(&mut self, _evm: &EVM, op: u8) {
+        if let Some(started) = self.started.take() {
+            *self.timings.entry(op).or_default() += started.elapsed();
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// 最小化的 RLP 编码，用于 CREATE 地址推导、回执/交易编码
+//
+// 规则：
+//   单个字节且 < 0x80：        原样输出
+//   长度 0~55 的字节串：       0x80 + len, 数据
+//   长度 > 55 的字节串：       0xb7 + len的字节数, len(大端), 数据
+//   总长 0~55 的列表：         0xc0 + len, 各元素编码
+//   总长 > 55 的列表：         0xf7 + len的字节数, len(大端), 各元素编码
+
+use primitive_types::U256;
+
+// 长度前缀：短数据为 offset + len，长数据为 offset + 55 + len的字节数, 再跟上 len
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = len.to_be_bytes();
+    let first = len_bytes.iter().position(|b| *b != 0).unwrap_or(len_bytes.len() - 1);
+    let len_bytes = &len_bytes[first..];
+    let mut out = vec![offset + 55 + len_bytes.len() as u8];
+    out.extend_from_slice(len_bytes);
+    out
+}
+
+/// 编码字节串
+pub fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    if data.len() == 1 && data[0] < 0x80 {
+        return data.to_vec();
+    }
+    let mut out = encode_length(data.len(), 0x80);
+    out.extend_from_slice(data);
+    out
+}
+
+/// 编码列表，items 为已经 RLP 编码好的各个元素
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.concat();
+    let mut out = encode_length(payload.len(), 0xc0);
+    out.extend(payload);
+    out
+}
+
+/// 编码整数：去掉前导 0 的大端序字节串，0 编码为空串 0x80
+pub fn encode_u256(value: U256) -> Vec<u8> {
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    let first = buf.iter().position(|b| *b != 0).unwrap_or(buf.len());
+    encode_bytes(&buf[first..])
+}
@@ -0,0 +1,130 @@
+// 单步执行、按指令数分段执行与单步回退，供交互式调试器使用
+//
+// 每次 `step` 前保存一份完整的状态快照（与 HistoryTracer 记录的是同一个时刻，
+// 但包含内存、存储、日志等全部内容），`step_back` 弹出最近的快照恢复状态。
+// 完整快照较占内存，最多保留 undo_depth 份，超出时丢弃最早的。`run` 不记录快照。
+
+use crate::*;
+use std::collections::VecDeque;
+
+// 默认最多可以回退的步数
+pub(crate) const DEFAULT_UNDO_DEPTH: usize = 256;
+
+pub(crate) type UndoHistory = VecDeque<UndoSnapshot>;
+
+/// `run_n` 返回时的执行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    // 执行了 n 条指令后暂停，程序尚未结束，可以继续 step/run
+    Paused,
+    // 程序已经停止
+    Finished,
+}
+
+// 执行一条指令前的完整状态
+#[derive(Clone)]
+pub(crate) struct UndoSnapshot {
+    pc: usize,
+    stack: Vec<U256>,
+    memory: Vec<u8>,
+    storage: HashMap<U256, U256>,
+    logs: Vec<Log>,
+    output: Vec<u8>,
+    return_data: Vec<u8>,
+    success: bool,
+    reverted: bool,
+    halted: bool,
+    gas_remaining: u64,
+    gas_ledger: GasLedger,
+    account_db: HashMap<Address, AccountInfo>,
+    accessed_addresses: HashSet<Address>,
+    accessed_storage_keys: HashSet<(Address, U256)>,
+}
+
+impl EVM {
+    /// 设置最多可以回退的步数，0 表示不记录快照
+    pub fn with_undo_depth(mut self, depth: usize) -> Self {
+        self.undo_depth = depth;
+        self
+    }
+
+    /// 执行一条指令，已经停止时什么都不做
+    pub fn step(&mut self) -> Result<(), EvmError> {
+        self.step_with_tracer(&mut NoopTracer)
+    }
+
+    /// 执行一条指令，前后回调 tracer
+    pub fn step_with_tracer(&mut self, tracer: &mut dyn Tracer) -> Result<(), EvmError> {
+        if self.is_halted() {
+            return Ok(());
+        }
+        if self.undo_depth > 0 {
+            if self.undo_history.len() == self.undo_depth {
+                self.undo_history.pop_front();
+            }
+            let snapshot = self.undo_snapshot();
+            self.undo_history.push_back(snapshot);
+        }
+        self.execute_instruction(tracer)
+    }
+
+    /// 最多执行 n 条指令后暂停（不会让程序停止），返回暂停还是已经结束
+    /// 某条指令出错时返回该错误，程序随之停止，之后再调用只会返回 Finished
+    pub fn run_n(&mut self, n: usize) -> Result<RunState, EvmError> {
+        for _ in 0..n {
+            if self.is_halted() {
+                break;
+            }
+            self.step()?;
+        }
+        Ok(if self.is_halted() { RunState::Finished } else { RunState::Paused })
+    }
+
+    /// 撤销最近一次 `step`，恢复到该指令执行前的状态；没有可回退的步骤时返回 false
+    pub fn step_back(&mut self) -> bool {
+        let Some(snapshot) = self.undo_history.pop_back() else {
+            return false;
+        };
+        self.pc = snapshot.pc;
+        self.stack = snapshot.stack;
+        self.memory = snapshot.memory;
+        self.storage = snapshot.storage;
+        self.logs = snapshot.logs;
+        self.output = snapshot.output;
+        self.return_data = snapshot.return_data;
+        self.success = snapshot.success;
+        self.reverted = snapshot.reverted;
+        self.halted = snapshot.halted;
+        self.gas_remaining = snapshot.gas_remaining;
+        self.gas_ledger = snapshot.gas_ledger;
+        self.account_db = snapshot.account_db;
+        self.accessed_addresses = snapshot.accessed_addresses;
+        self.accessed_storage_keys = snapshot.accessed_storage_keys;
+        true
+    }
+
+    /// 当前可以回退的步数
+    pub fn undo_len(&self) -> usize {
+        self.undo_history.len()
+    }
+
+    fn undo_snapshot(&self) -> UndoSnapshot {
+        UndoSnapshot {
+            pc: self.pc,
+            stack: self.stack.clone(),
+            memory: self.memory.clone(),
+            storage: self.storage.clone(),
+            logs: self.logs.clone(),
+            output: self.output.clone(),
+            return_data: self.return_data.clone(),
+            success: self.success,
+            reverted: self.reverted,
+            halted: self.halted,
+            gas_remaining: self.gas_remaining,
+            gas_ledger: self.gas_ledger,
+            account_db: self.account_db.clone(),
+            accessed_addresses: self.accessed_addresses.clone(),
+            accessed_storage_keys: self.accessed_storage_keys.clone(),
+        }
+    }
+}
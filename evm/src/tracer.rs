@@ -0,0 +1,84 @@
+// 执行追踪：在每条指令执行前后回调，用于调试器、日志、统计等
+
+use crate::EVM;
+use primitive_types::U256;
+
+/// 指令级追踪回调，默认实现均为空，按需覆盖
+pub trait Tracer {
+    // 指令执行前调用，此时 evm.pc() 指向该指令
+    fn before_op(&mut self, _evm: &EVM, _op: u8) {}
+
+    // 指令执行后调用
+    fn after_op(&mut self, _evm: &EVM, _op: u8) {}
+
+    // 指令写入内存时调用，bytes 为写入后的内容，在 after_op 之前触发
+    fn on_memory_write(&mut self, _offset: usize, _bytes: &[u8]) {}
+
+    // 指令读取内存时调用，在 after_op 之前触发
+    fn on_memory_read(&mut self, _offset: usize, _len: usize) {}
+}
+
+// 单条指令内发生的内存访问
+#[derive(Clone)]
+pub(crate) enum MemoryAccess {
+    Read { offset: usize, len: usize },
+    Write { offset: usize, bytes: Vec<u8> },
+}
+
+/// 什么都不做的 tracer，`run` 默认使用
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {}
+
+/// 单步执行前的状态快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepSnapshot {
+    pub pc: usize,
+    pub opcode: u8,
+    // 栈顶附近的元素（栈底——>栈顶），最多 stack_depth 个
+    pub stack: Vec<U256>,
+    pub memory_len: usize,
+    pub gas_remaining: u64,
+}
+
+/// 记录每一步执行前的状态，作为“时间回溯”调试的基础
+/// 完整的堆栈快照很占内存，可通过 `with_stack_depth` 只保留栈顶的若干元素
+#[derive(Debug, Default)]
+pub struct HistoryTracer {
+    stack_depth: Option<usize>,
+    history: Vec<StepSnapshot>,
+}
+
+impl HistoryTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 每个快照最多保存栈顶的 depth 个元素
+    pub fn with_stack_depth(mut self, depth: usize) -> Self {
+        self.stack_depth = Some(depth);
+        self
+    }
+
+    /// 按执行顺序返回记录的所有步骤
+    pub fn history(&self) -> &[StepSnapshot] {
+        &self.history
+    }
+}
+
+impl Tracer for HistoryTracer {
+    fn before_op(&mut self, evm: &EVM, op: u8) {
+        let stack = evm.stack();
+        let start = match self.stack_depth {
+            Some(depth) => stack.len().saturating_sub(depth),
+            None => 0,
+        };
+        self.history.push(StepSnapshot {
+            pc: evm.pc(),
+            opcode: op,
+            stack: stack[start..].to_vec(),
+            memory_len: evm.memory().len(),
+            gas_remaining: evm.gas_remaining(),
+        });
+    }
+}
@@ -0,0 +1,120 @@
+// 交易与区块执行：两级 gas 记账
+//
+//   交易级：每笔交易有自己的 gas 上限，执行中用完只让这笔交易失败（OutOfGas），
+//           gas 照常计入区块
+//   区块级：一笔交易的 gas 上限加上区块已用的 gas 超过区块 gas 上限时，
+//           这笔交易在执行前就被拒绝（BlockGasLimitExceeded），不计入区块
+//
+// 手续费（EIP-1559）：实际 gas 单价 = basefee + 小费单价，
+//   小费单价 = min(max_priority_fee_per_gas, max_fee_per_gas - basefee)
+//   gas_used * basefee 被销毁，gas_used * 小费单价 归出块者（coinbase）
+// 传统交易只有 gas_price，实际单价就是 gas_price，超出 basefee 的部分都是小费。
+// 单价上限低于 basefee 的交易在执行前被拒绝（FeeCapTooLow）。
+//
+// 每笔交易使用独立的 EVM 实例执行，交易之间只传递 coinbase 的余额（从 0 开始），
+// 每笔交易结束后小费记入其中，其余状态不共享。
+
+use crate::{BlockEnv, EvmError, ExecutionResult, FeeSplit, EVM};
+use primitive_types::U256;
+
+/// 一笔交易：要执行的代码、交易自身的 gas 上限和愿意支付的 gas 单价
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub code: Vec<u8>,
+    pub gas_limit: u64,
+    // 传统交易的 gas 单价，设置了 max_fee_per_gas 时不使用
+    pub gas_price: U256,
+    // EIP-1559 交易的最高 gas 单价和最高小费单价
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl Transaction {
+    pub fn new(code: Vec<u8>, gas_limit: u64) -> Self {
+        Self {
+            code,
+            gas_limit,
+            gas_price: U256::zero(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: U256::zero(),
+        }
+    }
+
+    /// 作为传统交易，以固定的 gas 单价付费
+    pub fn with_gas_price(mut self, gas_price: U256) -> Self {
+        self.gas_price = gas_price;
+        self
+    }
+
+    /// 作为 EIP-1559 交易，设置最高 gas 单价和最高小费单价
+    pub fn with_max_fees(mut self, max_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> Self {
+        self.max_fee_per_gas = Some(max_fee_per_gas);
+        self.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        self
+    }
+
+    /// 在给定 basefee 下的实际 gas 单价，单价上限低于 basefee 时返回 FeeCapTooLow
+    pub fn effective_gas_price(&self, base_fee: U256) -> Result<U256, EvmError> {
+        let fee_cap = self.max_fee_per_gas.unwrap_or(self.gas_price);
+        if fee_cap < base_fee {
+            return Err(EvmError::FeeCapTooLow { fee_cap, base_fee });
+        }
+        Ok(match self.max_fee_per_gas {
+            Some(max_fee) => base_fee + self.max_priority_fee_per_gas.min(max_fee - base_fee),
+            None => self.gas_price,
+        })
+    }
+}
+
+/// 已执行交易的回执
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub result: ExecutionResult,
+    // 区块中截至这笔交易（含）累计消耗的 gas
+    pub cumulative_gas_used: u64,
+    // 实际 gas 单价，GASPRICE 读到的也是它
+    pub effective_gas_price: U256,
+    // 交易支付的总手续费 gas_used * effective_gas_price，等于销毁部分与小费之和
+    pub fee_paid: U256,
+    pub fees: FeeSplit,
+    // 这笔交易结束后 coinbase 的余额，即区块中截至这笔交易（含）累计收到的小费
+    pub coinbase_balance: U256,
+}
+
+/// 按顺序执行区块中的交易，每笔交易对应一个结果：被区块 gas 上限或 basefee 拒绝的交易为 Err，
+/// 其余为回执（交易自身执行失败时 `result.success` 为 false）
+pub fn execute_block(block_env: &BlockEnv, transactions: &[Transaction]) -> Vec<Result<Receipt, EvmError>> {
+    let block_gas_limit = EVM::u256_to_u64_saturating(block_env.gaslimit);
+    let mut cumulative_gas_used: u64 = 0;
+    let mut coinbase_balance = U256::zero();
+    transactions
+        .iter()
+        .map(|tx| {
+            if cumulative_gas_used.saturating_add(tx.gas_limit) > block_gas_limit {
+                return Err(EvmError::BlockGasLimitExceeded {
+                    tx_gas_limit: tx.gas_limit,
+                    block_gas_used: cumulative_gas_used,
+                    block_gas_limit,
+                });
+            }
+            let effective_gas_price = tx.effective_gas_price(block_env.basefee)?;
+            let mut evm = EVM::new(tx.code.clone())
+                .with_block_env(block_env.clone())
+                .with_gas_limit(tx.gas_limit)
+                .with_gas_price(effective_gas_price);
+            evm.set_balance(block_env.coinbase, coinbase_balance);
+            let error = evm.run().err();
+            // 异常终止（如 OutOfGas）的交易消耗全部 gas 上限
+            let mut result = evm.execution_result(error);
+            if result.error.is_some() {
+                result.gas_used = tx.gas_limit;
+            }
+            cumulative_gas_used += result.gas_used;
+
+            let fees = evm.settle_fees_for(result.gas_used, effective_gas_price - block_env.basefee);
+            let fee_paid = fees.base_fee_burned.saturating_add(fees.priority_fee_paid);
+            coinbase_balance = evm.balance_of(block_env.coinbase);
+            Ok(Receipt { result, cumulative_gas_used, effective_gas_price, fee_paid, fees, coinbase_balance })
+        })
+        .collect()
+}
@@ -0,0 +1,93 @@
+// 账户与调用上下文：余额的读写、BALANCE 指令、当前合约账户和入口调用的参数
+
+use ethereum_types::Address;
+use evm::asm::assemble;
+use evm::EVM;
+use primitive_types::U256;
+
+fn balance_source(address: Address) -> String {
+    format!("PUSH20 0x{}\nBALANCE", hex::encode(address.as_bytes()))
+}
+
+#[test]
+fn balance_opcode_reads_balance_set_through_the_api() {
+    let alice = Address::from_low_u64_be(0xa11ce);
+    let mut evm = EVM::new(assemble(&balance_source(alice)).unwrap());
+    assert_eq!(evm.balance_of(alice), U256::zero());
+    evm.set_balance(alice, U256::from(1234));
+    assert_eq!(evm.balance_of(alice), U256::from(1234));
+    evm.run().unwrap();
+    assert_eq!(evm.stack(), &[U256::from(1234)]);
+}
+
+#[test]
+fn configured_contract_account_is_seen_by_self_opcodes() {
+    let contract = Address::from_low_u64_be(0xc0de);
+    let code = assemble("ADDRESS\nSELFBALANCE\nADDRESS\nBALANCE\nADDRESS\nEXTCODESIZE").unwrap();
+    let code_len = code.len();
+    // 先设置余额再换地址，余额随账户一起迁移
+    let mut evm = EVM::new(code).with_balance(U256::from(500)).with_address(contract);
+    evm.run().unwrap();
+
+    let word = U256::from_big_endian(contract.as_bytes());
+    assert_eq!(evm.stack(), &[word, U256::from(500), U256::from(500), U256::from(code_len)]);
+    assert_eq!(evm.balance_of(contract), U256::from(500));
+}
+
+#[test]
+fn for_call_sets_caller_value_and_calldata() {
+    let caller = Address::from_low_u64_be(0xca11e7);
+    let calldata = (1..=36u8).collect::<Vec<u8>>();
+    let code = assemble("PUSH1 0x04\nCALLDATALOAD\nCALLVALUE\nCALLER\nCALLDATASIZE").unwrap();
+    let mut evm = EVM::for_call(code, caller, U256::from(99), calldata.clone());
+    evm.run().unwrap();
+
+    assert_eq!(
+        evm.stack(),
+        &[
+            U256::from_big_endian(&calldata[4..36]),
+            U256::from(99),
+            U256::from_big_endian(caller.as_bytes()),
+            U256::from(36),
+        ]
+    );
+}
+
+#[test]
+fn transfer_value_with_insufficient_balance_changes_nothing() {
+    let alice = Address::from_low_u64_be(0xa11ce);
+    let bob = Address::from_low_u64_be(0xb0b);
+    let mut evm = EVM::new(vec![]);
+    evm.set_balance(alice, U256::from(100));
+    evm.set_balance(bob, U256::from(5));
+
+    assert_eq!(
+        evm.transfer_value(alice, bob, U256::from(101)),
+        Err(evm::EvmError::InsufficientBalance { required: U256::from(101), available: U256::from(100) })
+    );
+    assert_eq!((evm.balance_of(alice), evm.balance_of(bob)), (U256::from(100), U256::from(5)));
+
+    evm.set_balance(bob, U256::MAX);
+    assert_eq!(evm.transfer_value(alice, bob, U256::one()), Err(evm::EvmError::BalanceOverflow));
+    assert_eq!((evm.balance_of(alice), evm.balance_of(bob)), (U256::from(100), U256::MAX));
+
+    evm.transfer_value(alice, Address::zero(), U256::from(100)).unwrap();
+    assert_eq!((evm.balance_of(alice), evm.balance_of(Address::zero())), (U256::zero(), U256::from(100)));
+}
+
+#[test]
+fn dirty_address_errors_in_strict_mode_and_is_truncated_by_default() {
+    let alice = Address::from_low_u64_be(0xa11ce);
+    // 低 20 字节是 alice，高位多出一个 1
+    let dirty = U256::from_big_endian(alice.as_bytes()) | (U256::one() << 200);
+    let source = format!("PUSH32 0x{:064x}\nBALANCE", dirty);
+
+    let mut evm = EVM::new(assemble(&source).unwrap());
+    evm.set_balance(alice, U256::from(77));
+    evm.run().unwrap();
+    assert_eq!(evm.stack(), &[U256::from(77)]);
+
+    let mut evm = EVM::new(assemble(&source).unwrap()).with_strict_addresses(true);
+    evm.set_balance(alice, U256::from(77));
+    assert_eq!(evm.run(), Err(evm::EvmError::DirtyAddress(dirty)));
+}
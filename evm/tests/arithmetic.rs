@@ -0,0 +1,32 @@
+// 算术指令：除数为 0 的两种处理方式
+// 本 EVM 的 DIV/MOD 等计算“元素2 op 元素1”（元素1 为栈顶），所以除数是最后压入的值
+
+use evm::asm::assemble;
+use evm::{EvmError, EVM};
+use primitive_types::U256;
+
+fn run(source: &str, strict: bool) -> (EVM, Result<(), EvmError>) {
+    let mut evm = EVM::new(assemble(source).unwrap()).with_trap_div_by_zero(strict);
+    let result = evm.run();
+    (evm, result)
+}
+
+#[test]
+fn division_by_zero_pushes_zero_by_default() {
+    for op in ["DIV", "MOD", "SDIV", "SMOD"] {
+        let (evm, result) = run(&format!("PUSH1 0x07\nPUSH1 0x00\n{}", op), false);
+        result.unwrap();
+        assert_eq!(evm.stack(), &[U256::zero()], "{}", op);
+    }
+}
+
+#[test]
+fn strict_mode_traps_division_by_zero() {
+    for op in ["DIV", "MOD", "SDIV", "SMOD"] {
+        let (_, result) = run(&format!("PUSH1 0x07\nPUSH1 0x00\n{}", op), true);
+        assert_eq!(result, Err(EvmError::DivisionByZero), "{}", op);
+    }
+    let (evm, result) = run("PUSH1 0x07\nPUSH1 0x02\nDIV", true);
+    result.unwrap();
+    assert_eq!(evm.stack(), &[U256::from(3)], "除数非 0 时不受影响");
+}
@@ -0,0 +1,22 @@
+// 汇编器相关工具：带注释的反汇编
+
+use evm::asm::{annotate, assemble};
+
+#[test]
+fn annotate_lists_pc_gas_and_stack_effect() {
+    // main.rs 中的示例程序：PUSH1 0x0a, PUSH1 0x01, PUSH1 0x00, INVALID, MSTORE
+    let code = vec![0x60, 0x0a, 0x60, 0x01, 0x60, 0x00, 0xfe, 0x52];
+    let listing = annotate(&code);
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines.len(), 5, "PUSH 的立即数不单独成行");
+    assert_eq!(lines[0], "   0: PUSH1 0x0a       gas=3     stack=+1");
+    assert_eq!(lines[4], "   7: MSTORE           gas=3     stack=-2");
+}
+
+#[test]
+fn annotate_round_trips_through_the_assembler() {
+    let code = assemble("PUSH2 0x5b5b\nJUMPDEST\nSTOP").unwrap();
+    let listing = annotate(&code);
+    let pcs: Vec<&str> = listing.lines().map(|line| line.split(':').next().unwrap().trim()).collect();
+    assert_eq!(pcs, vec!["0", "3", "4"]);
+}
@@ -0,0 +1,23 @@
+// 批量并行执行互不相关的程序
+
+use evm::asm::assemble;
+use evm::run_many;
+use primitive_types::U256;
+
+#[test]
+fn run_many_keeps_input_order_for_a_hundred_programs() {
+    let programs: Vec<Vec<u8>> = (0..100u64)
+        .map(|i| assemble(&format!("PUSH1 0x{:02x}\nDUP1\nADD", i)).unwrap())
+        .collect();
+    let results = run_many(programs);
+    assert_eq!(results.len(), 100);
+    for (i, result) in results.iter().enumerate() {
+        assert!(result.success, "第 {} 个程序失败", i);
+        assert_eq!(result.stack, vec![U256::from(2 * i)], "第 {} 个程序", i);
+    }
+}
+
+#[test]
+fn run_many_on_no_programs_returns_nothing() {
+    assert!(run_many(vec![]).is_empty());
+}
@@ -0,0 +1,147 @@
+// 区块环境与区块执行：区块信息指令、两级 gas 记账与 EIP-1559 手续费
+
+use evm::asm::assemble;
+use ethereum_types::{Address, H256};
+use evm::{execute_block, BlockEnv, EvmError, Transaction, EVM};
+use primitive_types::U256;
+
+fn block_env(base_fee: u64) -> BlockEnv {
+    BlockEnv::new().with_basefee(U256::from(base_fee)).with_gaslimit(U256::from(10_000_000))
+}
+
+#[test]
+fn block_info_opcodes_read_the_custom_block_env() {
+    let env = BlockEnv::new()
+        .with_blockhash(H256::repeat_byte(0xbb))
+        .with_coinbase(Address::repeat_byte(0xcc))
+        .with_timestamp(U256::from(1_700_000_000u64))
+        .with_number(U256::from(42))
+        .with_prevrandao(H256::repeat_byte(0xdd))
+        .with_gaslimit(U256::from(15_000_000))
+        .with_chainid(U256::from(5))
+        .with_basefee(U256::from(7))
+        .with_blobbasefee(U256::from(3));
+    let code = assemble("PUSH1 0x2a\nBLOCKHASH\nCOINBASE\nTIMESTAMP\nNUMBER\nPREVRANDAO\nGASLIMIT\nCHAINID\nBASEFEE\nBLOBBASEFEE").unwrap();
+    let mut evm = EVM::new(code).with_block_env(env);
+    evm.run().unwrap();
+    let word = |bytes: &[u8]| U256::from_big_endian(bytes);
+    assert_eq!(
+        evm.stack(),
+        &[
+            word(&[0xbb; 32]),
+            word(&[0xcc; 20]),
+            U256::from(1_700_000_000u64),
+            U256::from(42),
+            word(&[0xdd; 32]),
+            U256::from(15_000_000),
+            U256::from(5),
+            U256::from(7),
+            U256::from(3),
+        ]
+    );
+}
+
+#[test]
+fn eip1559_effective_price_burn_and_tip() {
+    let code = assemble("GASPRICE").unwrap();
+    // basefee 10，最高单价 30，最高小费 5：实际单价 15
+    let tx = Transaction::new(code, 100_000).with_max_fees(U256::from(30), U256::from(5));
+    let receipt = execute_block(&block_env(10), &[tx]).remove(0).unwrap();
+
+    let gas_used = U256::from(receipt.result.gas_used);
+    assert_eq!(receipt.effective_gas_price, U256::from(15));
+    assert_eq!(receipt.result.stack, vec![U256::from(15)], "GASPRICE 应压入实际单价");
+    assert_eq!(receipt.fees.base_fee_burned, gas_used * 10);
+    assert_eq!(receipt.fees.priority_fee_paid, gas_used * 5);
+    assert_eq!(receipt.fee_paid, gas_used * 15);
+}
+
+#[test]
+fn priority_fee_is_capped_by_max_fee() {
+    let tx = Transaction::new(vec![], 100_000).with_max_fees(U256::from(30), U256::from(50));
+    let receipt = execute_block(&block_env(10), &[tx]).remove(0).unwrap();
+    assert_eq!(receipt.effective_gas_price, U256::from(30));
+}
+
+#[test]
+fn fee_cap_below_base_fee_is_rejected() {
+    let txs = [
+        Transaction::new(vec![], 100_000).with_gas_price(U256::from(8)),
+        Transaction::new(vec![], 100_000).with_max_fees(U256::from(9), U256::from(1)),
+    ];
+    for result in execute_block(&block_env(10), &txs) {
+        assert!(matches!(result, Err(EvmError::FeeCapTooLow { .. })));
+    }
+}
+
+#[test]
+fn coinbase_receives_only_the_priority_fee() {
+    let coinbase = ethereum_types::Address::from_low_u64_be(0xc0ffee);
+    let env = block_env(10).with_coinbase(coinbase);
+    let txs = [
+        Transaction::new(assemble("PUSH1 0x01").unwrap(), 100_000).with_max_fees(U256::from(30), U256::from(5)),
+        Transaction::new(assemble("COINBASE\nBALANCE").unwrap(), 100_000).with_gas_price(U256::from(12)),
+    ];
+    let receipts: Vec<_> = execute_block(&env, &txs).into_iter().map(Result::unwrap).collect();
+
+    let first_tip = U256::from(receipts[0].result.gas_used) * 5;
+    assert_eq!(receipts[0].coinbase_balance, first_tip, "coinbase 只收到小费，basefee 部分被销毁");
+    assert_eq!(receipts[0].fees.priority_fee_paid, first_tip);
+    assert_eq!(receipts[0].fee_paid - receipts[0].coinbase_balance, receipts[0].fees.base_fee_burned);
+
+    // 第二笔交易执行时能读到第一笔交易的小费，结束后再加上自己的小费
+    assert_eq!(receipts[1].result.stack, vec![first_tip]);
+    let second_tip = U256::from(receipts[1].result.gas_used) * 2;
+    assert_eq!(receipts[1].coinbase_balance, first_tip + second_tip);
+}
+
+#[test]
+fn transaction_exceeding_block_gas_limit_is_rejected_while_first_succeeds() {
+    let env = BlockEnv::new().with_basefee(U256::zero()).with_gaslimit(U256::from(60_002));
+    let txs = [
+        Transaction::new(assemble("PUSH1 0x01").unwrap(), 60_000),
+        Transaction::new(assemble("PUSH1 0x01").unwrap(), 60_000),
+    ];
+    let results = execute_block(&env, &txs);
+
+    let first = results[0].as_ref().unwrap();
+    assert!(first.result.success);
+    assert_eq!(first.cumulative_gas_used, 3);
+    assert_eq!(
+        results[1],
+        Err(EvmError::BlockGasLimitExceeded { tx_gas_limit: 60_000, block_gas_used: 3, block_gas_limit: 60_002 })
+    );
+}
+
+#[test]
+fn transaction_out_of_its_own_gas_fails_but_counts_its_gas_limit() {
+    let env = BlockEnv::new().with_basefee(U256::zero()).with_gaslimit(U256::from(100));
+    let txs = [Transaction::new(assemble("PUSH1 0x01\nPUSH1 0x01").unwrap(), 5)];
+    let receipt = execute_block(&env, &txs).remove(0).unwrap();
+    assert!(!receipt.result.success);
+    assert!(matches!(receipt.result.error, Some(EvmError::OutOfGas { .. })));
+    assert_eq!(receipt.cumulative_gas_used, 5);
+}
+
+// 用固定的变量表代替进程环境变量
+fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |name| pairs.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+}
+
+#[test]
+fn block_env_from_vars_overrides_only_the_set_fields() {
+    let env = BlockEnv::from_vars(vars(&[("EVM_TIMESTAMP", " 1700000000 "), ("EVM_CHAINID", "0x05")])).unwrap();
+    let default = BlockEnv::default();
+    assert_eq!(env.timestamp, U256::from(1_700_000_000u64));
+    assert_eq!(env.chainid, U256::from(5));
+    assert_eq!(env.number, default.number);
+    assert_eq!(env.coinbase, default.coinbase);
+}
+
+#[test]
+fn block_env_from_vars_reports_the_bad_variable() {
+    let err = BlockEnv::from_vars(vars(&[("EVM_NUMBER", "12abc")])).unwrap_err();
+    assert_eq!(err, EvmError::InvalidEnvVar { name: "EVM_NUMBER".to_string(), value: "12abc".to_string() });
+    let err = BlockEnv::from_vars(vars(&[("EVM_COINBASE", "0x1234")])).unwrap_err();
+    assert!(matches!(err, EvmError::InvalidEnvVar { name, .. } if name == "EVM_COINBASE"));
+}
@@ -0,0 +1,22 @@
+// 字节码片段构造函数
+
+use evm::builder::{mstore_word, push_u256, return_range};
+use evm::eval_return;
+use primitive_types::U256;
+
+#[test]
+fn push_u256_uses_the_shortest_push() {
+    assert_eq!(push_u256(U256::zero()), vec![0x5f]);
+    assert_eq!(push_u256(U256::from(255)), vec![0x60, 0xff]);
+    assert_eq!(push_u256(U256::from(256)), vec![0x61, 0x01, 0x00]);
+    let max = push_u256(U256::MAX);
+    assert_eq!((max[0], max.len()), (0x7f, 33));
+}
+
+#[test]
+fn mstore_word_and_return_range_compose() {
+    let mut code = mstore_word(0, U256::from(42));
+    code.extend(return_range(0, 32));
+    let output = eval_return(&hex::encode(code)).unwrap();
+    assert_eq!(U256::from_big_endian(&output), U256::from(42));
+}
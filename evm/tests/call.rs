@@ -0,0 +1,160 @@
+// CALL 系列指令的返回数据、gas 转发与子上下文
+
+mod common;
+
+use common::{call_source, evm_with_callees, run_with_callees};
+use ethereum_types::Address;
+use primitive_types::U256;
+
+// RETURN 32 字节 0x2a
+const RETURNS_WORD: &str = "PUSH1 0x2a\nPUSH1 0x00\nMSTORE\nPUSH1 0x20\nPUSH1 0x00\nRETURN";
+
+#[test]
+fn callee_stopping_after_its_own_call_returns_empty_data() {
+    let a = Address::from_low_u64_be(0xaa);
+    let b = Address::from_low_u64_be(0xbb);
+    let c = Address::from_low_u64_be(0xcc);
+    // B 调用返回 32 字节的 C，然后以 STOP 结束
+    let code_b = format!("{}POP\nSTOP", call_source(c, 0, 0));
+    let code_a = format!("{}RETURNDATASIZE", call_source(b, 0, 32));
+    let mut evm = evm_with_callees(a, &code_a, &[(b, &code_b), (c, RETURNS_WORD)]).with_memory(vec![0xee; 32]);
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::one(), U256::zero()], "CALL 应压入 1，RETURNDATASIZE 应为 0");
+    assert_eq!(evm.memory(), &[0xee; 32], "返回区域不应被写入");
+}
+
+#[test]
+fn top_level_stop_after_call_has_no_output() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    let code_a = format!("{}RETURNDATASIZE\nSTOP", call_source(c, 0, 0));
+    let evm = run_with_callees(a, &code_a, &[(c, RETURNS_WORD)]);
+
+    assert_eq!(evm.stack(), &[U256::one(), U256::from(32)]);
+    assert!(evm.execution_result(None).return_data.is_empty());
+}
+
+// 跳过一个 INVALID 后返回 32 字节 0x07，需要合法的跳转表才能执行成功
+const JUMPS_THEN_RETURNS: &str = "PUSH1 0x04\nJUMP\nINVALID\nJUMPDEST\nPUSH1 0x07\nPUSH1 0x00\nMSTORE\nPUSH1 0x20\nPUSH1 0x00\nRETURN";
+
+#[test]
+fn repeated_calls_reuse_the_cached_jump_table() {
+    let a = Address::from_low_u64_be(0xaa);
+    let b = Address::from_low_u64_be(0xbb);
+    let c = Address::from_low_u64_be(0xcc);
+    let code_a = format!(
+        "{}{}{}{}",
+        call_source(b, 0x00, 32),
+        call_source(b, 0x20, 32),
+        call_source(b, 0x40, 32),
+        call_source(c, 0x60, 32)
+    );
+    let evm = run_with_callees(a, &code_a, &[(b, JUMPS_THEN_RETURNS), (c, RETURNS_WORD)]);
+
+    assert_eq!(evm.stack(), &[U256::one(); 4]);
+    for offset in [0x00, 0x20, 0x40] {
+        assert_eq!(U256::from_big_endian(&evm.memory()[offset..offset + 32]), U256::from(7));
+    }
+    let cache = evm.code_cache();
+    assert_eq!(cache.len(), 2, "不同的代码应各占一项");
+    assert_eq!(cache.misses(), 2);
+    assert_eq!(cache.hits(), 2, "后两次调用 B 应命中缓存");
+}
+
+#[test]
+fn call_gas_clamps_huge_requests_to_the_63_64_cap() {
+    let evm = evm::EVM::new(vec![]).with_gas_limit(6400);
+    assert_eq!(evm.call_gas(U256::MAX), 6300);
+    assert_eq!(evm.call_gas(U256::from(u64::MAX) + 1), 6300);
+    assert_eq!(evm.call_gas(U256::from(100)), 100);
+}
+
+#[test]
+fn call_with_max_gas_argument_succeeds_instead_of_panicking() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    let max = format!("PUSH32 0x{}", "ff".repeat(32));
+    let code_a = call_source(c, 0, 0).replace("PUSH2 0xffff", &max);
+    let evm = run_with_callees(a, &code_a, &[(c, RETURNS_WORD)]);
+    assert_eq!(evm.stack(), &[U256::one()]);
+}
+
+#[test]
+fn registered_call_response_lands_in_memory() {
+    let a = Address::from_low_u64_be(0xaa);
+    let oracle = Address::from_low_u64_be(0x0c);
+    let input = vec![0x12, 0x34, 0x56, 0x78];
+    // 以内存 [0, 4) 为输入调用 oracle，返回数据写到 [0x20, 0x24)，之后读出 RETURNDATASIZE
+    let code_a = "PUSH1 0x04\nPUSH1 0x20\nPUSH1 0x04\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x0c\nPUSH2 0xffff\nCALL\nRETURNDATASIZE";
+    let mut evm = evm_with_callees(a, code_a, &[]).with_memory(input.clone());
+    evm.register_call_response(oracle, input, true, vec![0xca, 0xfe, 0xba, 0xbe, 0xff]);
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::one(), U256::from(5)]);
+    assert_eq!(&evm.memory()[0x20..0x25], &[0xca, 0xfe, 0xba, 0xbe, 0x00], "只复制 retLength 字节");
+}
+
+#[test]
+fn unregistered_input_falls_through_to_execution() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    let mut evm = evm_with_callees(a, &format!("{}RETURNDATASIZE", call_source(c, 0, 0)), &[(c, RETURNS_WORD)]);
+    evm.register_call_response(c, vec![0x01], false, vec![]);
+    evm.run().unwrap();
+    assert_eq!(evm.stack(), &[U256::one(), U256::from(32)]);
+}
+
+#[test]
+fn invalid_in_callee_consumes_forwarded_gas_while_revert_refunds_it() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    let code_a = call_source(c, 0, 0);
+    let reverted = run_with_callees(a, &code_a, &[(c, "PUSH1 0x00\nPUSH1 0x00\nREVERT")]);
+    let invalid = run_with_callees(a, &code_a, &[(c, "PUSH1 0x00\nPUSH1 0x00\nINVALID")]);
+
+    assert_eq!(reverted.stack(), &[U256::zero()]);
+    assert_eq!(invalid.stack(), &[U256::zero()]);
+    // REVERT 只消耗两条 PUSH1 的 6 gas，INVALID 消耗转发的全部 0xffff gas
+    assert_eq!(invalid.gas_used() - reverted.gas_used(), 0xffff - 6);
+}
+
+#[test]
+fn calling_an_address_without_code_does_not_create_the_account() {
+    let a = Address::from_low_u64_be(0xaa);
+    let empty = Address::from_low_u64_be(0xdd);
+    let code_a = format!("{}PUSH1 0xdd\nEXTCODEHASH", call_source(empty, 0, 0));
+    let evm = run_with_callees(a, &code_a, &[]);
+    assert_eq!(evm.stack(), &[U256::one(), U256::zero()], "调用成功，账户仍不存在");
+}
+
+#[test]
+fn registered_call_response_still_charges_and_transfers_value() {
+    let a = Address::from_low_u64_be(0xaa);
+    let oracle = Address::from_low_u64_be(0x0c);
+    // 向 oracle 转账 7，不传参数
+    let code_a = "PUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x07\nPUSH1 0x0c\nPUSH2 0xffff\nCALL";
+    let mut evm = evm_with_callees(a, code_a, &[]);
+    evm.register_call_response(oracle, vec![], true, vec![]);
+    let caller_balance = evm.balance_of(a);
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::one()]);
+    assert_eq!(evm.balance_of(oracle), U256::from(7));
+    assert_eq!(evm.balance_of(a), caller_balance - 7);
+    // 7 条 PUSH 21，cold 地址 100 + 2500，转账 9000
+    assert_eq!(evm.gas_used(), 21 + 100 + 2500 + 9000);
+}
+
+#[test]
+fn failed_registered_call_response_keeps_the_value() {
+    let a = Address::from_low_u64_be(0xaa);
+    let oracle = Address::from_low_u64_be(0x0c);
+    let code_a = "PUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x07\nPUSH1 0x0c\nPUSH2 0xffff\nCALL";
+    let mut evm = evm_with_callees(a, code_a, &[]);
+    evm.register_call_response(oracle, vec![], false, vec![]);
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::zero()]);
+    assert_eq!(evm.balance_of(oracle), U256::zero());
+}
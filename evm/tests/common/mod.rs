@@ -0,0 +1,33 @@
+// 集成测试共用的辅助函数
+
+#![allow(dead_code)]
+
+use ethereum_types::Address;
+use evm::asm::assemble;
+use evm::EVM;
+
+/// 以 caller 为入口合约构造 EVM，callees 中的合约预先部署好（尚未执行）
+pub fn evm_with_callees(caller: Address, code: &str, callees: &[(Address, &str)]) -> EVM {
+    let mut evm = EVM::new(assemble(code).unwrap()).with_address(caller).with_gas_limit(1_000_000);
+    for (address, source) in callees {
+        evm.set_code(*address, assemble(source).unwrap());
+    }
+    evm
+}
+
+/// 与 `evm_with_callees` 相同，并执行到结束
+pub fn run_with_callees(caller: Address, code: &str, callees: &[(Address, &str)]) -> EVM {
+    let mut evm = evm_with_callees(caller, code, callees);
+    evm.run().unwrap();
+    evm
+}
+
+/// 调用 address 的 CALL 指令序列（value 为 0，不传参数），返回数据写入内存 [ret_offset, ret_offset + ret_len)
+pub fn call_source(address: Address, ret_offset: u8, ret_len: u8) -> String {
+    format!(
+        "PUSH1 0x{:02x}\nPUSH1 0x{:02x}\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH20 0x{}\nPUSH2 0xffff\nCALL\n",
+        ret_len,
+        ret_offset,
+        hex::encode(address.as_bytes())
+    )
+}
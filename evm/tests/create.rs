@@ -0,0 +1,112 @@
+// CREATE：地址推导、nonce 递增、嵌套部署与部署的代码
+
+mod common;
+
+use common::call_source;
+use ethereum_types::Address;
+use evm::asm::assemble;
+use evm::EVM;
+use primitive_types::U256;
+
+// 以内存中的 init_code 执行 CREATE（value 为 0），之后执行 rest
+fn create_source(init_code: &[u8], rest: &str) -> String {
+    format!("PUSH1 0x{:02x}\nPUSH1 0x00\nPUSH1 0x00\nCREATE\n{}", init_code.len(), rest)
+}
+
+fn address_word(address: Address) -> U256 {
+    U256::from_big_endian(address.as_bytes())
+}
+
+#[test]
+fn factory_init_code_deploys_a_child_at_nonce_derived_addresses() {
+    // 子合约的初始化代码：部署 1 字节的代码 0xaa
+    let child_init = assemble("PUSH1 0xaa\nPUSH1 0x00\nMSTORE8\nPUSH1 0x01\nPUSH1 0x00\nRETURN").unwrap();
+    // 工厂的初始化代码：CREATE 子合约，把子合约地址存到槽 0，然后部署 1 字节的代码 0xbb
+    let factory_init = assemble(&format!(
+        "PUSH10 0x{}
+        PUSH1 0x00
+        MSTORE
+        PUSH1 0x{:02x}
+        PUSH1 0x{:02x}
+        PUSH1 0x00
+        CREATE
+        PUSH1 0x00
+        SSTORE
+        PUSH1 0xbb
+        PUSH1 0x00
+        MSTORE8
+        PUSH1 0x01
+        PUSH1 0x00
+        RETURN",
+        hex::encode(&child_init),
+        child_init.len(),
+        32 - child_init.len(),
+    ))
+    .unwrap();
+    let code = assemble(&create_source(&factory_init, "PUSH1 0x00\nSSTORE")).unwrap();
+    let mut evm = EVM::new(code).with_memory(factory_init).with_nonce(U256::from(5)).with_gas_limit(1_000_000);
+    evm.run().unwrap();
+
+    let deployer = evm.address();
+    let factory = EVM::create_address(deployer, U256::from(5));
+    // 新合约的 nonce 从 1 开始，工厂的第一次 CREATE 使用 nonce 1
+    let child = EVM::create_address(factory, U256::one());
+    assert_eq!(evm.storage_of(deployer, U256::zero()), address_word(factory));
+    assert_eq!(evm.storage_of(factory, U256::zero()), address_word(child));
+    assert_eq!(evm.nonce(deployer), U256::from(6));
+    assert_eq!(evm.nonce(factory), U256::from(2));
+    assert_eq!(evm.nonce(child), U256::one());
+}
+
+#[test]
+fn init_code_that_calls_and_stops_deploys_empty_code() {
+    let returner = Address::from_low_u64_be(0xcc);
+    let init = assemble(&format!("{}POP\nSTOP", call_source(returner, 0, 0))).unwrap();
+    let code = assemble(&create_source(&init, "EXTCODESIZE")).unwrap();
+    let mut evm = EVM::new(code).with_memory(init).with_gas_limit(1_000_000);
+    evm.set_code(returner, assemble("PUSH1 0x2a\nPUSH1 0x00\nMSTORE\nPUSH1 0x20\nPUSH1 0x00\nRETURN").unwrap());
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::zero()], "被调用方的返回数据不应被部署为代码");
+}
+
+#[test]
+fn create_address_matches_known_vector() {
+    let sender: Address = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0".parse().unwrap();
+    let expected: Address = "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d".parse().unwrap();
+    assert_eq!(EVM::create_address(sender, U256::zero()), expected);
+}
+
+#[test]
+fn sequential_creates_derive_different_addresses() {
+    let code = assemble(&create_source(&[], &create_source(&[], ""))).unwrap();
+    let mut evm = EVM::new(code).with_nonce(U256::one()).with_gas_limit(1_000_000);
+    evm.run().unwrap();
+
+    let deployer = evm.address();
+    let first = EVM::create_address(deployer, U256::one());
+    let second = EVM::create_address(deployer, U256::from(2));
+    assert_ne!(first, second);
+    assert_eq!(evm.stack(), &[address_word(first), address_word(second)]);
+    assert_eq!(evm.nonce(deployer), U256::from(3));
+}
+
+#[test]
+fn create_onto_existing_code_fails_and_keeps_the_code() {
+    let init = assemble("PUSH1 0xaa\nPUSH1 0x00\nMSTORE8\nPUSH1 0x01\nPUSH1 0x00\nRETURN").unwrap();
+    let target = EVM::create_address(EVM::new(vec![]).address(), U256::one());
+    let existing = assemble("PUSH1 0x01\nSTOP").unwrap();
+    // CREATE 之后读出目标地址的代码长度，并把代码复制到内存 0x40
+    let check = format!(
+        "PUSH20 0x{0}\nEXTCODESIZE\nPUSH1 0x{1:02x}\nPUSH1 0x00\nPUSH1 0x40\nPUSH20 0x{0}\nEXTCODECOPY",
+        hex::encode(target.as_bytes()),
+        existing.len()
+    );
+    let code = assemble(&create_source(&init, &check)).unwrap();
+    let mut evm = EVM::new(code).with_memory(init).with_nonce(U256::one()).with_gas_limit(1_000_000);
+    evm.set_code(target, existing.clone());
+    evm.run().unwrap();
+
+    assert_eq!(evm.stack(), &[U256::zero(), U256::from(existing.len())], "冲突时 CREATE 压入 0");
+    assert_eq!(&evm.memory()[0x40..0x40 + existing.len()], &existing[..], "已有代码不应被覆盖");
+}
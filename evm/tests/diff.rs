@@ -0,0 +1,31 @@
+// 两个 EVM 状态之间的差异
+
+use evm::asm::assemble;
+use evm::{diff_state, SlotDiff, EVM};
+use primitive_types::U256;
+
+fn run(source: &str) -> EVM {
+    let mut evm = EVM::new(assemble(source).unwrap());
+    evm.run().unwrap();
+    evm
+}
+
+#[test]
+fn unchanged_clone_has_no_diff() {
+    let evm = run("PUSH1 0x07\nPUSH1 0x01\nSSTORE\nPUSH1 0x02\nPUSH1 0x00\nMSTORE");
+    let diff = diff_state(&evm, &evm.clone());
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "状态无变化\n");
+}
+
+#[test]
+fn single_changed_slot_is_the_only_diff() {
+    let before = run("PUSH1 0x07\nPUSH1 0x01\nSSTORE");
+    let mut after = before.clone();
+    after.set_storage(after.address(), U256::one(), U256::from(8));
+
+    let diff = diff_state(&before, &after);
+    assert_eq!(diff.storage, vec![SlotDiff { key: U256::one(), before: U256::from(7), after: U256::from(8) }]);
+    assert!(diff.stack.is_none() && diff.memory.is_none() && diff.logs.is_none());
+    assert!(diff.balances.is_empty() && diff.gas_used.is_none());
+}
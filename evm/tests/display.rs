@@ -0,0 +1,124 @@
+// 状态输出与各种格式化辅助函数
+
+use evm::asm::assemble;
+use evm::{DisplayOptions, NumberBase, EVM};
+use primitive_types::U256;
+
+fn run(source: &str) -> EVM {
+    let mut evm = EVM::new(assemble(source).unwrap());
+    evm.run().unwrap();
+    evm
+}
+
+// 取 Display 输出中 header 与下一个段落之间的行
+fn section(text: &str, header: &str) -> Vec<String> {
+    text.lines()
+        .skip_while(|line| line.trim() != header)
+        .skip(1)
+        .take_while(|line| line.starts_with("      "))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn storage_slots_print_on_separate_lines() {
+    let text = run("PUSH1 0x02\nPUSH1 0x01\nSSTORE\nPUSH1 0x04\nPUSH1 0x03\nSSTORE").to_string();
+    assert_eq!(section(&text, "存储Storage："), vec!["1: 2", "3: 4"]);
+}
+
+#[test]
+fn storage_entries_are_sorted_and_paginated() {
+    // 按 3、1、5、2 的顺序写入，槽 5 随后被清零
+    let evm = run("
+        PUSH1 0x30\nPUSH1 0x03\nSSTORE
+        PUSH1 0x10\nPUSH1 0x01\nSSTORE
+        PUSH1 0x50\nPUSH1 0x05\nSSTORE
+        PUSH1 0x20\nPUSH1 0x02\nSSTORE
+        PUSH1 0x00\nPUSH1 0x05\nSSTORE
+    ");
+    let entry = |key: u64, value: u64| (U256::from(key), U256::from(value));
+    assert_eq!(evm.storage_entries_sorted(), vec![entry(1, 0x10), entry(2, 0x20), entry(3, 0x30)]);
+    assert_eq!(evm.storage_entries_page(1, 1), vec![entry(2, 0x20)]);
+    assert_eq!(evm.storage_entries_page(2, 10), vec![entry(3, 0x30)]);
+    assert!(evm.storage_entries_page(3, 10).is_empty());
+    assert_eq!(section(&evm.to_string(), "存储Storage："), vec!["1: 16", "2: 32", "3: 48"]);
+}
+
+#[test]
+fn empty_storage_prints_a_clean_header() {
+    let text = run("PUSH1 0x01").to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    let header = lines.iter().position(|line| line.trim() == "存储Storage：").unwrap();
+    assert_eq!(lines[header + 1].trim(), "日志Logs:");
+}
+
+#[test]
+fn context_listing_marks_the_halting_stop() {
+    // 0,2,4,6: PUSH1  8: STOP  9..: 之后的指令不会执行
+    let evm = run("PUSH1 0x01\nPUSH1 0x02\nPUSH1 0x03\nPUSH1 0x04\nSTOP\nADD\nADD\nADD\nADD");
+    let listing = evm.context_listing();
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines.len(), 7, "前后各 3 条指令");
+    let marked: Vec<&&str> = lines.iter().filter(|line| line.starts_with("=>")).collect();
+    assert_eq!(marked.len(), 1);
+    assert!(marked[0].contains("8: STOP"), "{}", marked[0]);
+    assert!(lines[0].contains("2: PUSH1"), "{}", lines[0]);
+}
+
+#[test]
+fn format_word_shows_address_and_bytes32_views_when_verbose() {
+    let value = U256::from(0xabcdu64);
+    assert_eq!(evm::format_word(value, false), "默认十进制=43981, 十六进制=0xabcd");
+    assert_eq!(
+        evm::format_word(value, true),
+        format!(
+            "默认十进制=43981, 十六进制=0xabcd, 地址=0x{}abcd, bytes32=0x{}abcd",
+            "0".repeat(36),
+            "0".repeat(60)
+        )
+    );
+}
+
+#[test]
+fn format_push_shows_hex_and_decimal() {
+    assert_eq!(evm::format_push(&[0x01, 0x02]), "识别PUSH2指令，压入0x0102（十进制258）");
+    assert_eq!(evm::format_push(&[0xff]), "识别PUSH1指令，压入0xff（十进制255）");
+}
+
+#[test]
+fn fmt_with_signed_hex_shows_minus_one_for_max() {
+    let evm = run(&format!("PUSH32 0x{}\nPUSH1 0x05", "ff".repeat(32)));
+    let opts = DisplayOptions::new().with_base(NumberBase::Hex).with_signed(true);
+    let text = evm.fmt_with(&opts);
+    assert!(text.contains("第 0 位 有符号十六进制=-0x1"), "{}", text);
+    assert!(text.contains("第 1 位 有符号十六进制=0x5"), "{}", text);
+
+    let text = evm.fmt_with(&opts.with_max_stack_slots(1));
+    assert!(text.contains("（省略栈底 1 项）") && !text.contains("第 0 位"), "{}", text);
+}
+
+#[test]
+fn default_display_matches_fmt_with_default_options() {
+    let evm = run("PUSH1 0x05");
+    assert_eq!(evm.to_string(), evm.fmt_with(&DisplayOptions::new()));
+}
+
+#[test]
+fn memory_groups_of_four_bytes_on_sixteen_bytes() {
+    let memory: Vec<u8> = (0..16).collect();
+    let opts = DisplayOptions::new().with_memory_group_size(4);
+    assert_eq!(
+        evm::display::format_memory(&memory, &opts),
+        vec!["00010203 04050607 08090a0b 0c0d0e0f"]
+    );
+    assert_eq!(evm::display::format_memory(&memory, &DisplayOptions::new()), vec!["000102030405060708090a0b0c0d0e0f"]);
+}
+
+#[test]
+fn memory_rows_stay_32_bytes_with_small_groups() {
+    let memory = vec![0xaa; 40];
+    let rows = evm::display::format_memory(&memory, &DisplayOptions::new().with_memory_group_size(4));
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].split(' ').count(), 8);
+    assert_eq!(rows[1], "aaaaaaaa aaaaaaaa");
+}
@@ -0,0 +1,39 @@
+// EIP-3540 EOF 容器的结构校验
+
+use evm::eof::{EofContainer, EofError, TypeSection};
+
+// 最小的合法容器：一个代码段（STOP），两字节数据
+const VALID: &str = "ef00 01 010004 0200010001 040002 00 00800001 00 aabb";
+
+fn parse(hex: &str) -> Result<EofContainer, EofError> {
+    EofContainer::parse(&evm::decode_hex(hex).unwrap())
+}
+
+#[test]
+fn minimal_container_parses() {
+    let container = parse(VALID).unwrap();
+    assert_eq!(container.version, 1);
+    assert_eq!(container.types, vec![TypeSection { inputs: 0, outputs: 0x80, max_stack_height: 1 }]);
+    assert_eq!(container.code_sections, vec![vec![0x00]]);
+    assert_eq!(container.data, vec![0xaa, 0xbb]);
+}
+
+#[test]
+fn malformed_containers_are_rejected() {
+    let cases = [
+        ("ef01 01 010004 0200010001 040002 00 00800001 00 aabb", EofError::InvalidMagic),
+        ("ef00 02 010004 0200010001 040002 00 00800001 00 aabb", EofError::UnsupportedVersion(2)),
+        ("ef00 01 0100", EofError::IncompleteHeader),
+        ("ef00 01 020004 0200010001 040002 00 00800001 00 aabb", EofError::UnexpectedSectionKind { expected: 1, found: 2 }),
+        ("ef00 01 010004 0200010001 040002 ff 00800001 00 aabb", EofError::MissingTerminator(0xff)),
+        ("ef00 01 010004 020000 040002 00 00800001 aabb", EofError::InvalidCodeSectionCount(0)),
+        ("ef00 01 010004 0200010000 040002 00 00800001 aabb", EofError::EmptyCodeSection(0)),
+        ("ef00 01 010008 0200010001 040002 00 00800001 00 aabb", EofError::InvalidTypesSize { expected: 4, found: 8 }),
+        ("ef00 01 010004 0200010001 040002 00 00800001 00 aa", EofError::BodySizeMismatch { expected: 7, found: 6 }),
+        ("ef00 01 010004 0200010001 040002 00 00800400 00 aabb", EofError::InvalidTypeEntry { section: 0, reason: "max_stack_height 超过 1023" }),
+        ("ef00 01 010004 0200010001 040002 00 00000001 00 aabb", EofError::InvalidFirstSectionType),
+    ];
+    for (hex, expected) in cases {
+        assert_eq!(parse(hex), Err(expected), "{}", hex);
+    }
+}
@@ -0,0 +1,177 @@
+// 端到端场景：执行一个最小的 ERC-20 合约的 transfer(address,uint256)
+//
+// 余额存在槽 0 的 mapping 中，地址 a 的余额位于 keccak256(pad32(a) . pad32(0))。
+// transfer 从 CALLER 的余额中扣除 amount 加到 to 上，发出 Transfer(from, to, amount) 的 LOG3 并返回 true；
+// 选择器不匹配或余额不足时 REVERT。
+// 注意本 EVM 的 DIV/SUB/LT 计算的是“元素2 op 元素1”（元素1 为栈顶），合约按这个约定编写。
+
+use ethereum_types::{Address, H256};
+use evm::asm::{assemble, disassemble};
+use evm::{selector, EVM};
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+const TOKEN_SOURCE: &str = "
+    // 取 calldata 前 4 字节作为选择器
+    PUSH1 0x00
+    CALLDATALOAD
+    PUSH29 0x0100000000000000000000000000000000000000000000000000000000
+    DIV
+    PUSH4 {selector}
+    EQ
+    PUSH1 {transfer}
+    JUMPI
+    PUSH1 0x00
+    DUP1
+    REVERT
+
+    JUMPDEST            // transfer
+    CALLER
+    PUSH1 0x00
+    MSTORE
+    PUSH1 0x00
+    PUSH1 0x20
+    MSTORE
+    PUSH1 0x40
+    PUSH1 0x00
+    SHA3                // [fromSlot]
+    DUP1
+    SLOAD               // [fromSlot, fromBalance]
+    PUSH1 0x24
+    CALLDATALOAD        // [fromSlot, fromBalance, amount]
+    DUP2
+    DUP2
+    LT
+    PUSH1 {revert}
+    JUMPI               // fromBalance < amount 时 REVERT
+    SWAP1
+    DUP2
+    SUB                 // [fromSlot, amount, fromBalance - amount]
+    DUP3
+    SSTORE
+    SWAP1
+    POP                 // [amount]
+
+    PUSH1 0x04
+    CALLDATALOAD
+    PUSH1 0x00
+    MSTORE
+    PUSH1 0x40
+    PUSH1 0x00
+    SHA3                // [amount, toSlot]
+    DUP1
+    SLOAD
+    DUP3
+    ADD
+    SWAP1
+    SSTORE              // [amount]
+
+    PUSH1 0x00
+    MSTORE              // 日志数据为 amount
+    PUSH1 0x04
+    CALLDATALOAD
+    CALLER
+    PUSH32 {transfer_topic}
+    PUSH1 0x20
+    PUSH1 0x00
+    LOG3
+
+    PUSH1 0x01
+    PUSH1 0x00
+    MSTORE
+    PUSH1 0x20
+    PUSH1 0x00
+    RETURN
+
+    JUMPDEST            // revert
+    PUSH1 0x00
+    DUP1
+    REVERT
+";
+
+fn transfer_topic() -> H256 {
+    H256::from_slice(&Keccak256::digest(b"Transfer(address,address,uint256)"))
+}
+
+// 汇编合约：先用 0 占位跳转目标，得到两个 JUMPDEST 的位置后再填入
+fn token_code() -> Vec<u8> {
+    let source = |transfer: usize, revert: usize| {
+        TOKEN_SOURCE
+            .replace("{selector}", &format!("0x{}", hex::encode(selector("transfer(address,uint256)"))))
+            .replace("{transfer_topic}", &format!("0x{}", hex::encode(transfer_topic())))
+            .replace("{transfer}", &transfer.to_string())
+            .replace("{revert}", &revert.to_string())
+    };
+    let draft = assemble(&source(0, 0)).unwrap();
+    let jumpdests: Vec<usize> =
+        disassemble(&draft).iter().filter(|ins| ins.mnemonic() == "JUMPDEST").map(|ins| ins.pc).collect();
+    assemble(&source(jumpdests[0], jumpdests[1])).unwrap()
+}
+
+fn balance_slot(owner: Address) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[12..32].copy_from_slice(owner.as_bytes());
+    U256::from_big_endian(&Keccak256::digest(preimage))
+}
+
+fn transfer_calldata(to: Address, amount: U256) -> Vec<u8> {
+    let mut calldata = selector("transfer(address,uint256)").to_vec();
+    calldata.extend_from_slice(&[0u8; 12]);
+    calldata.extend_from_slice(to.as_bytes());
+    let mut word = [0u8; 32];
+    amount.to_big_endian(&mut word);
+    calldata.extend_from_slice(&word);
+    calldata
+}
+
+// 以 from 的身份调用代币合约的 transfer，from 事先持有 funded 个代币
+fn run_transfer(from: Address, to: Address, funded: U256, amount: U256) -> (EVM, Result<(), evm::EvmError>) {
+    let token = Address::from_low_u64_be(0x7070);
+    let mut evm = EVM::for_call(token_code(), from, U256::zero(), transfer_calldata(to, amount))
+        .with_address(token)
+        .with_gas_limit(1_000_000);
+    evm.set_storage(token, balance_slot(from), funded);
+    let result = evm.run();
+    (evm, result)
+}
+
+#[test]
+fn erc20_transfer_moves_balances_and_emits_transfer_log() {
+    let alice = Address::from_low_u64_be(0xa11ce);
+    let bob = Address::from_low_u64_be(0xb0b);
+    let (evm, result) = run_transfer(alice, bob, U256::from(1000), U256::from(250));
+    result.unwrap();
+    let token = evm.address();
+
+    let outcome = evm.execution_result(None);
+    assert!(outcome.success);
+    assert_eq!(U256::from_big_endian(&outcome.return_data), U256::one(), "transfer 应返回 true");
+    assert_eq!(evm.storage_of(token, balance_slot(alice)), U256::from(750));
+    assert_eq!(evm.storage_of(token, balance_slot(bob)), U256::from(250));
+
+    let logs = evm.logs();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].address, token);
+    assert_eq!(logs[0].topics, vec![transfer_topic(), H256::from(alice), H256::from(bob)]);
+    assert_eq!(U256::from_big_endian(&logs[0].data), U256::from(250));
+}
+
+#[test]
+fn erc20_transfer_reverts_when_balance_is_insufficient() {
+    let alice = Address::from_low_u64_be(0xa11ce);
+    let bob = Address::from_low_u64_be(0xb0b);
+    let (evm, result) = run_transfer(alice, bob, U256::from(100), U256::from(250));
+    result.unwrap();
+    let token = evm.address();
+
+    assert!(!evm.execution_result(None).success);
+    assert_eq!(evm.storage_of(token, balance_slot(alice)), U256::from(100));
+    assert_eq!(evm.storage_of(token, balance_slot(bob)), U256::zero());
+    assert!(evm.logs().is_empty());
+}
+
+#[test]
+fn selector_matches_known_erc20_selectors() {
+    assert_eq!(selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+}
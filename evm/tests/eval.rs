@@ -0,0 +1,38 @@
+// 一行执行十六进制字节码的便捷函数
+
+use evm::{eval, eval_return, eval_stack, EvmError};
+use primitive_types::U256;
+
+#[test]
+fn eval_returns_top_of_stack() {
+    assert_eq!(eval("6002600301"), Ok(U256::from(5)));
+    assert_eq!(eval("0x60 02 60 03 01"), Ok(U256::from(5)));
+}
+
+#[test]
+fn eval_on_a_return_program_reads_the_return_data() {
+    // PUSH1 2, PUSH1 3, ADD, MSTORE 到 0，RETURN 0..32
+    let program = "600260030160005260206000f3";
+    assert_eq!(eval(program), Err(EvmError::StackUnderflow { required: 1, actual: 0 }));
+    assert_eq!(U256::from_big_endian(&eval_return(program).unwrap()), U256::from(5));
+}
+
+#[test]
+fn eval_rejects_bad_hex() {
+    assert!(matches!(eval("60zz"), Err(EvmError::InvalidHex(_))));
+}
+
+#[test]
+fn eval_stack_returns_every_item_bottom_to_top() {
+    assert_eq!(eval_stack("600160026003"), Ok(vec![U256::from(1), U256::from(2), U256::from(3)]));
+    assert_eq!(eval_stack(""), Ok(vec![]));
+}
+
+#[test]
+fn eval_return_reads_return_and_revert_data() {
+    // MSTORE8 0xab 到 0，RETURN 0..2
+    assert_eq!(eval_return("60ab60005360026000f3"), Ok(vec![0xab, 0x00]));
+    // 同样的数据用 REVERT 返回
+    assert_eq!(eval_return("60ab60005360026000fd"), Ok(vec![0xab, 0x00]));
+    assert_eq!(eval_return("6001"), Ok(vec![]), "没有 RETURN 时为空");
+}
@@ -0,0 +1,57 @@
+// StateExpectation 自测
+
+use evm::asm::assemble;
+use evm::expect::StateExpectation;
+use evm::EVM;
+use primitive_types::U256;
+
+fn run(source: &str) -> EVM {
+    let mut evm = EVM::new(assemble(source).unwrap());
+    evm.run().unwrap();
+    evm
+}
+
+#[test]
+fn matching_expectation_passes() {
+    let evm = run("PUSH1 0x07\nPUSH1 0x01\nSSTORE\nPUSH1 0xaa\nPUSH1 0x00\nMSTORE8\nPUSH1 0x00\nPUSH1 0x00\nLOG0\nPUSH1 0x03");
+    StateExpectation::new()
+        .stack([U256::from(3)])
+        .storage(U256::one(), U256::from(7))
+        .storage(U256::from(2), U256::zero())
+        .memory_at(0, &[0xaa, 0x00])
+        .log_count(1)
+        .gas_used(evm.gas_used())
+        .assert(&evm);
+}
+
+#[test]
+fn mismatches_are_all_listed() {
+    let evm = run("PUSH1 0x03");
+    let message = StateExpectation::new()
+        .stack([U256::from(4)])
+        .storage(U256::zero(), U256::one())
+        .memory_at(0, &[0x01])
+        .log_count(2)
+        .gas_used(1)
+        .check(&evm)
+        .unwrap_err();
+    let lines: Vec<&str> = message.lines().collect();
+    assert_eq!(lines.len(), 5, "{}", message);
+    assert_eq!(lines[0], "堆栈不匹配：期望 [4]，实际 [3]");
+    assert_eq!(lines[1], "存储槽 0 不匹配：期望 1，实际 0");
+    assert_eq!(lines[2], "内存 [0..1) 越界：当前内存只有 0 字节");
+    assert_eq!(lines[3], "日志数量不匹配：期望 2，实际 0");
+    assert_eq!(lines[4], "gas 消耗不匹配：期望 1，实际 3");
+}
+
+#[test]
+#[should_panic(expected = "执行后状态与期望不符")]
+fn assert_panics_on_mismatch() {
+    StateExpectation::new().stack([]).assert(&run("PUSH1 0x03"));
+}
+
+#[test]
+fn overflowing_memory_range_is_a_mismatch_not_a_panic() {
+    let message = StateExpectation::new().memory_at(usize::MAX, &[0x01]).check(&run("PUSH1 0x03")).unwrap_err();
+    assert_eq!(message, format!("内存 [{}..{}+1) 越界：范围超出 usize", usize::MAX, usize::MAX));
+}
@@ -0,0 +1,75 @@
+// gas 计量：各类操作码的收费组成
+
+use evm::asm::assemble;
+use evm::EVM;
+use primitive_types::U256;
+
+fn gas_used(source: &str) -> u64 {
+    let mut evm = EVM::new(assemble(source).unwrap());
+    evm.run().unwrap();
+    assert!(evm.execution_result(None).success, "{}", source);
+    evm.gas_used()
+}
+
+#[test]
+fn log_gas_charges_per_topic_and_per_byte() {
+    let log0 = gas_used("PUSH1 0x00\nPUSH1 0x00\nLOG0");
+    assert_eq!(log0, 2 * 3 + 375);
+
+    let log2 = gas_used("PUSH1 0x02\nPUSH1 0x01\nPUSH1 0x40\nPUSH1 0x00\nLOG2");
+    // 4 次 PUSH1 + 375 基础 + 2 * 375 topic + 64 * 8 数据 + 2 个字的内存扩展（2 * 3，二次项为 0）
+    let memory = 2 * 3;
+    assert_eq!(log2, 4 * 3 + 375 + 2 * 375 + 64 * 8 + memory);
+    assert_eq!(log2 - log0, 2 * 3 + 2 * 375 + 64 * 8 + memory);
+}
+
+#[test]
+fn gas_ledger_splits_storage_memory_and_computation() {
+    let mut evm = EVM::new(
+        assemble("PUSH1 0x01\nPUSH1 0x00\nSSTORE\nPUSH1 0x20\nPUSH1 0x00\nSHA3\nPUSH1 0x02\nPUSH1 0x20\nMSTORE").unwrap(),
+    );
+    evm.run().unwrap();
+    let ledger = evm.execution_result(None).gas_ledger;
+
+    // SSTORE：cold 槽 2100 + 从 0 写成非 0 的 20000
+    assert_eq!(ledger.storage, 2100 + 20000);
+    // SHA3 扩展到 1 个字，MSTORE 再扩展到 2 个字
+    assert_eq!(ledger.memory, 2 * 3);
+    // 6 次 PUSH1、SHA3 的 30 + 每字 6、MSTORE 的 3
+    assert_eq!(ledger.computation, 6 * 3 + 30 + 6 + 3);
+    assert_eq!((ledger.logs, ledger.calls, ledger.account_access), (0, 0, 0));
+    assert_eq!(ledger.total(), evm.gas_used());
+}
+
+#[test]
+fn extcodesize_is_cold_then_warm_and_self_starts_warm() {
+    // 两次 EXTCODESIZE 同一个新地址：第一次 cold 2600，第二次 warm 100
+    let twice = gas_used("PUSH1 0xcc\nEXTCODESIZE\nPUSH1 0xcc\nEXTCODESIZE");
+    assert_eq!(twice, 3 + 2600 + 3 + 100);
+
+    // 当前合约自身的地址一开始就是 warm 的
+    let own = gas_used("ADDRESS\nEXTCODESIZE\nADDRESS\nBALANCE");
+    assert_eq!(own, 2 + 100 + 2 + 100);
+}
+
+#[test]
+fn warm_slot_makes_the_first_sload_warm() {
+    let code = assemble("PUSH1 0x01\nSLOAD").unwrap();
+    let mut cold = EVM::new(code.clone());
+    cold.run().unwrap();
+    // cold 槽合计 2100（固定的 100 加上 2000 的 cold 附加费）
+    assert_eq!(cold.gas_used(), 3 + 2100);
+
+    let address = cold.address();
+    let mut warm = EVM::new(code).warm_slot(address, U256::one());
+    warm.run().unwrap();
+    assert_eq!(warm.gas_used(), 3 + 100);
+}
+
+#[test]
+fn memory_cost_saturates_instead_of_overflowing() {
+    assert_eq!(evm::gas::memory_cost(usize::MAX), u64::MAX);
+    // 2^32 - 1 个字时平方项刚好不溢出
+    let words = u64::from(u32::MAX);
+    assert_eq!(evm::gas::memory_cost((words * 32) as usize), 3 * words + words * words / 512);
+}
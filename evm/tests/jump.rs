@@ -0,0 +1,41 @@
+// 跳转目标分析：JUMPDEST 的识别、jump_destinations 接口，以及空代码等边界
+
+use evm::asm::assemble;
+use evm::{EvmError, EVM};
+use primitive_types::U256;
+
+#[test]
+fn leading_jumpdest_runs_and_is_a_jump_target() {
+    let mut evm = EVM::new(assemble("JUMPDEST\nPUSH1 0x01\nSTOP").unwrap());
+    evm.run().unwrap();
+    assert!(evm.execution_result(None).success);
+    assert_eq!(evm.stack(), &[U256::one()]);
+    assert_eq!(evm.gas_used(), 4, "JUMPDEST 1 + PUSH1 3");
+    assert_eq!(evm.jump_destinations(), vec![0]);
+}
+
+#[test]
+fn jumpdest_inside_push_data_is_not_a_target() {
+    // 0: PUSH2 0x5b5b（两个 0x5b 都是立即数）  3: JUMPDEST  4: PUSH1 0x5b（立即数）  6: JUMPDEST
+    let evm = EVM::new(assemble("PUSH2 0x5b5b\nJUMPDEST\nPUSH1 0x5b\nJUMPDEST").unwrap());
+    assert_eq!(evm.jump_destinations(), vec![3, 6]);
+}
+
+#[test]
+fn jump_into_push_data_is_rejected() {
+    // 偏移 4 是 PUSH1 的立即数 0x5b
+    let mut evm = EVM::new(assemble("PUSH1 0x04\nJUMP\nPUSH1 0x5b").unwrap());
+    assert_eq!(evm.run(), Err(EvmError::InvalidJump(4)));
+}
+
+#[test]
+fn empty_code_halts_cleanly_at_pc0() {
+    let mut evm = EVM::new(vec![]);
+    assert!(evm.jump_destinations().is_empty());
+    evm.run().unwrap();
+    let result = evm.execution_result(None);
+    assert!(result.success);
+    assert!(result.stack.is_empty() && result.return_data.is_empty());
+    assert_eq!((evm.pc(), evm.gas_used()), (0, 0));
+    assert_eq!(evm.context_listing(), "");
+}
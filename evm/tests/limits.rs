@@ -0,0 +1,95 @@
+// 宿主保护：返回数据、内存的上限，以及超大操作数不会导致 panic
+
+use evm::asm::assemble;
+use evm::{EvmError, EVM};
+
+fn run(source: &str, configure: impl FnOnce(EVM) -> EVM) -> (EVM, Result<(), EvmError>) {
+    let mut evm = configure(EVM::new(assemble(source).unwrap()));
+    let result = evm.run();
+    (evm, result)
+}
+
+#[test]
+fn return_beyond_cap_errors() {
+    let (_, result) = run("PUSH1 0x41\nPUSH1 0x00\nRETURN", |evm| evm.with_max_return_data_size(64));
+    assert_eq!(result, Err(EvmError::ReturnDataTooLarge { size: 65, limit: 64 }));
+}
+
+#[test]
+fn return_just_under_cap_succeeds() {
+    let (evm, result) = run("PUSH1 0x40\nPUSH1 0x00\nRETURN", |evm| evm.with_max_return_data_size(64));
+    result.unwrap();
+    assert_eq!(evm.execution_result(None).return_data, vec![0; 64]);
+}
+
+#[test]
+fn return_and_revert_with_huge_length_error_instead_of_panicking() {
+    let max = format!("PUSH32 0x{}", "ff".repeat(32));
+    for op in ["RETURN", "REVERT"] {
+        let (_, result) = run(&format!("{}\nPUSH1 0x00\n{}", max, op), |evm| evm);
+        assert!(matches!(result, Err(EvmError::ReturnDataTooLarge { size: usize::MAX, .. })), "{}：{:?}", op, result);
+    }
+}
+
+#[test]
+fn huge_offsets_and_lengths_error_instead_of_panicking() {
+    let max = format!("PUSH32 0x{}", "ff".repeat(32));
+    let programs = [
+        format!("PUSH1 0x01\n{}\nMSTORE8", max),
+        format!("{}\nMLOAD", max),
+        format!("{}\nJUMP", max),
+        format!("PUSH1 0x01\n{}\nJUMPI", max),
+        format!("PUSH1 0x20\nPUSH1 0x00\n{}\nADDRESS\nEXTCODECOPY", max),
+        format!("PUSH1 0x01\nPUSH1 0x00\n{}\nADDRESS\nEXTCODECOPY", max),
+        format!("PUSH1 0x20\nPUSH1 0x00\n{}\nRETURNDATACOPY", max),
+        format!("PUSH1 0x00\n{}\nPUSH1 0x00\nRETURNDATACOPY", max),
+        format!("{}\nPUSH1 0x00\nPUSH1 0x00\nRETURNDATACOPY", max),
+    ];
+    for source in &programs {
+        let (_, result) = run(source, |evm| evm);
+        assert!(result.is_err(), "{} 应当出错", source);
+    }
+}
+
+#[test]
+fn extcodecopy_with_huge_code_offset_zero_fills() {
+    let max = format!("PUSH32 0x{}", "ff".repeat(32));
+    let (evm, result) = run(&format!("PUSH1 0x20\n{}\nPUSH1 0x00\nADDRESS\nEXTCODECOPY", max), |evm| evm);
+    result.unwrap();
+    assert_eq!(evm.memory(), &[0; 32]);
+}
+
+#[test]
+fn run_many_survives_programs_with_huge_operands() {
+    let bad = assemble(&format!("PUSH32 0x{}\nMLOAD", "ff".repeat(32))).unwrap();
+    let good = assemble("PUSH1 0x01").unwrap();
+    let results = evm::run_many(vec![bad, good]);
+    assert_eq!(results[0].error, Some(EvmError::MemoryOverflow));
+    assert!(results[1].success);
+}
+
+#[test]
+fn sha3_over_gigabytes_errors_before_allocating() {
+    // 长度 4 GiB，远超默认的 32 MiB 内存上限
+    let (evm, result) = run("PUSH5 0x0100000000\nPUSH1 0x00\nSHA3", |evm| evm);
+    assert_eq!(result, Err(EvmError::MemoryOverflow));
+    assert!(evm.memory().is_empty());
+}
+
+#[test]
+fn infinite_loop_stops_at_the_wall_clock_limit() {
+    let limit = std::time::Duration::from_millis(50);
+    let (_, result) = run("JUMPDEST\nPUSH1 0x00\nJUMP", |evm| evm.with_gas_limit(u64::MAX).with_max_duration(limit));
+    assert_eq!(result, Err(EvmError::Timeout { limit }));
+}
+
+#[test]
+fn sha3_at_the_memory_cap_succeeds_and_one_byte_over_fails() {
+    let (evm, result) = run("PUSH1 0x40\nPUSH1 0x00\nSHA3", |evm| evm.with_max_memory_size(64));
+    result.unwrap();
+    assert_eq!(evm.memory().len(), 64);
+
+    let (evm, result) = run("PUSH1 0x41\nPUSH1 0x00\nSHA3", |evm| evm.with_max_memory_size(64));
+    assert_eq!(result, Err(EvmError::MemoryOverflow));
+    assert!(evm.memory().is_empty());
+}
@@ -0,0 +1,29 @@
+// 静态检查一定会发生的堆栈下溢
+
+use evm::asm::assemble;
+use evm::lint::{check_stack_underflow, StackUnderflowLint};
+
+fn lint(source: &str) -> Option<StackUnderflowLint> {
+    check_stack_underflow(&assemble(source).unwrap())
+}
+
+#[test]
+fn add_as_the_first_instruction_underflows_at_pc0() {
+    let found = lint("ADD\nSTOP").unwrap();
+    assert_eq!(found, StackUnderflowLint { pc: 0, opcode: 0x01, required: 2, available: 0 });
+    assert_eq!(found.to_string(), "pc 0 处的 ADD 需要2个堆栈元素，此时只有0个");
+}
+
+#[test]
+fn underflow_after_some_pushes_reports_its_pc() {
+    let found = lint("PUSH1 0x01\nPUSH1 0x02\nADD\nADD").unwrap();
+    assert_eq!((found.pc, found.available), (5, 1));
+}
+
+#[test]
+fn sample_program_and_code_behind_jumpdest_are_not_flagged() {
+    // main.rs 中的示例程序：PUSH1 0x0a, PUSH1 0x01, PUSH1 0x00, INVALID, MSTORE
+    assert_eq!(check_stack_underflow(&[0x60, 0x0a, 0x60, 0x01, 0x60, 0x00, 0xfe, 0x52]), None);
+    assert_eq!(lint("JUMPDEST\nADD"), None, "JUMPDEST 处的堆栈高度未知");
+    assert_eq!(lint("PUSH1 0x00\nJUMP\nADD"), None, "JUMP 之后的代码只能通过跳转到达");
+}
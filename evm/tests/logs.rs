@@ -0,0 +1,30 @@
+// 日志的按 topic、按地址过滤
+
+mod common;
+
+use common::{call_source, run_with_callees};
+use ethereum_types::{Address, H256};
+use primitive_types::U256;
+
+#[test]
+fn logs_filter_by_topic_and_by_address() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    // A 发出 topic 为 [1] 和 [2, 1] 的日志，C 发出 topic 为 [2] 的日志
+    let code_a = format!(
+        "PUSH1 0x01\nPUSH1 0x00\nPUSH1 0x00\nLOG1\nPUSH1 0x01\nPUSH1 0x02\nPUSH1 0x00\nPUSH1 0x00\nLOG2\n{}",
+        call_source(c, 0, 0)
+    );
+    let evm = run_with_callees(a, &code_a, &[(c, "PUSH1 0x02\nPUSH1 0x00\nPUSH1 0x00\nLOG1")]);
+    assert_eq!(evm.stack(), &[U256::one()]);
+    assert_eq!(evm.logs().len(), 3);
+
+    let topic = |n: u64| H256::from_low_u64_be(n);
+    let topics_of = |logs: Vec<&evm::Log>| logs.into_iter().map(|log| log.topics.clone()).collect::<Vec<_>>();
+    assert_eq!(topics_of(evm.logs_with_topic(topic(1))), vec![vec![topic(1)], vec![topic(2), topic(1)]]);
+    assert_eq!(topics_of(evm.logs_with_topic(topic(2))), vec![vec![topic(2), topic(1)], vec![topic(2)]]);
+    assert!(evm.logs_with_topic(topic(3)).is_empty());
+    assert_eq!(topics_of(evm.logs_from(c)), vec![vec![topic(2)]]);
+    assert_eq!(evm.logs_from(a).len(), 2);
+    assert!(evm.logs_from(Address::zero()).is_empty());
+}
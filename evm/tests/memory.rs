@@ -0,0 +1,42 @@
+// 内存读写：预置内存镜像、MSTORE/MLOAD 的窗口与边界
+
+use evm::asm::assemble;
+use evm::EVM;
+use primitive_types::U256;
+
+#[test]
+fn mload_reads_a_window_of_the_preset_memory() {
+    let image: Vec<u8> = (0..64).collect();
+    let mut evm = EVM::new(assemble("PUSH1 0x05\nMLOAD").unwrap()).with_memory(image);
+    evm.run().unwrap();
+    let expected: Vec<u8> = (5..37).collect();
+    assert_eq!(evm.stack(), &[U256::from_big_endian(&expected)]);
+}
+
+#[test]
+fn log_data_past_memory_is_zero_filled() {
+    let mut evm = EVM::new(assemble("PUSH1 0x20\nPUSH1 0x10\nLOG0").unwrap()).with_memory(vec![0xab; 32]);
+    evm.run().unwrap();
+    let mut expected = vec![0xab; 16];
+    expected.extend_from_slice(&[0; 16]);
+    assert_eq!(evm.logs()[0].data, expected);
+    assert_eq!(evm.memory().len(), 64, "内存按字扩展以覆盖日志数据区域");
+}
+
+#[test]
+fn mstore_at_max_offset_errors_instead_of_panicking() {
+    let source = format!("PUSH1 0x01\nPUSH32 0x{}\nMSTORE", "ff".repeat(32));
+    let mut evm = EVM::new(assemble(&source).unwrap());
+    assert_eq!(evm.run(), Err(evm::EvmError::MemoryOverflow));
+    assert!(evm.memory().is_empty());
+}
+
+#[test]
+fn mstore_then_mload_round_trips_and_charges_expansion() {
+    let mut evm = EVM::new(assemble("PUSH2 0xbeef\nPUSH1 0x21\nMSTORE\nPUSH1 0x21\nMLOAD").unwrap());
+    evm.run().unwrap();
+    assert_eq!(evm.stack(), &[U256::from(0xbeef)]);
+    assert_eq!(evm.memory().len(), 96, "[0x21, 0x41) 需要 3 个字");
+    // 3 次 PUSH、MSTORE、MLOAD 各 3，外加 3 个字的内存扩展
+    assert_eq!(evm.gas_used(), 5 * 3 + 3 * 3);
+}
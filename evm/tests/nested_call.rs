@@ -0,0 +1,48 @@
+// 嵌套调用回滚的端到端场景
+//
+// 合约 A 先写自己的槽 1，再 CALL 合约 B；B 写自己的槽 2 后 REVERT。
+// 调用结束后 A 的槽 1 保留、B 的槽 2 被回滚，CALL 压入 0，A 继续执行后面的指令。
+
+mod common;
+
+use common::run_with_callees;
+use ethereum_types::Address;
+use primitive_types::U256;
+
+#[test]
+fn revert_in_nested_call_keeps_caller_storage_and_discards_callee_storage() {
+    let a = Address::from_low_u64_be(0xaa);
+    let b = Address::from_low_u64_be(0xbb);
+    let code_a = "
+        PUSH1 0x01
+        PUSH1 0x01
+        SSTORE          // A.slot1 = 1
+        PUSH1 0x00      // retLength
+        PUSH1 0x00      // retOffset
+        PUSH1 0x00      // argsLength
+        PUSH1 0x00      // argsOffset
+        PUSH1 0x00      // value
+        PUSH1 0xbb      // addr
+        PUSH2 0xffff    // gas
+        CALL
+        PUSH1 0x01
+        PUSH1 0x03
+        SSTORE          // A.slot3 = 1，证明 CALL 之后 A 继续执行
+        STOP
+    ";
+    let code_b = "
+        PUSH1 0x02
+        PUSH1 0x02
+        SSTORE          // B.slot2 = 2
+        PUSH1 0x00
+        PUSH1 0x00
+        REVERT
+    ";
+    let evm = run_with_callees(a, code_a, &[(b, code_b)]);
+
+    assert_eq!(evm.stack(), &[U256::zero()], "B REVERT 后 CALL 应压入 0");
+    assert_eq!(evm.storage_of(a, U256::from(1)), U256::from(1), "A 在调用前写入的槽 1 应保留");
+    assert_eq!(evm.storage_of(b, U256::from(2)), U256::zero(), "B 写入的槽 2 应被回滚");
+    assert_eq!(evm.storage_of(a, U256::from(3)), U256::from(1), "A 在 CALL 之后应继续执行");
+    assert!(evm.execution_result(None).success);
+}
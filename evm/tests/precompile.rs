@@ -0,0 +1,96 @@
+// KZG 点求值预编译（0x0A）：直接调用与通过 CALL 调用
+
+use evm::asm::assemble;
+use evm::precompile::{self, kzg_to_versioned_hash, PrecompileError, POINT_EVALUATION_ADDRESS, POINT_EVALUATION_GAS};
+use evm::EVM;
+use primitive_types::U256;
+
+const BLS_MODULUS: &str = "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+// versioned_hash 与 commitment 对应、z 和 y 都是合法域元素的输入
+fn valid_input() -> Vec<u8> {
+    let commitment = [0xc0; 48];
+    let mut input = kzg_to_versioned_hash(&commitment).to_vec();
+    input.extend_from_slice(&[0; 31]);
+    input.push(0x02); // z
+    input.extend_from_slice(&[0; 31]);
+    input.push(0x03); // y
+    input.extend_from_slice(&commitment);
+    input.extend_from_slice(&[0; 48]); // proof
+    input
+}
+
+fn success_output() -> Vec<u8> {
+    let mut output = vec![0u8; 64];
+    U256::from(4096).to_big_endian(&mut output[..32]);
+    U256::from_dec_str(BLS_MODULUS).unwrap().to_big_endian(&mut output[32..]);
+    output
+}
+
+// 以内存中的 [0, args_len) 为输入调用 0x0A，返回数据写到 [192, 256)
+fn call_point_evaluation(memory: Vec<u8>, args_len: usize, gas: u64) -> EVM {
+    let source = format!(
+        "PUSH1 0x40\nPUSH1 0xc0\nPUSH1 0x{:02x}\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x0a\nPUSH3 0x{:06x}\nCALL",
+        args_len, gas
+    );
+    let mut evm = EVM::new(assemble(&source).unwrap()).with_memory(memory).with_gas_limit(1_000_000);
+    evm.run().unwrap();
+    evm
+}
+
+#[test]
+fn valid_input_returns_the_success_output() {
+    let input = valid_input();
+    assert_eq!(input.len(), 192);
+    assert_eq!(precompile::point_evaluation(&input), Ok(success_output()));
+}
+
+#[test]
+fn versioned_hash_matches_an_independent_sha256() {
+    // 0x01 || sha256(0xc0 * 48)[1..]，由其他 SHA-256 实现算出
+    assert_eq!(
+        hex::encode(kzg_to_versioned_hash(&[0xc0; 48])),
+        "01e2f35246044cb2646ad1a00d9373fe9e4c129324952976bc2cecb9c31952b7"
+    );
+}
+
+#[test]
+fn malformed_lengths_error() {
+    for len in [0, 191, 193] {
+        assert_eq!(
+            precompile::point_evaluation(&vec![0; len]),
+            Err(PrecompileError::InvalidInputLength { expected: 192, found: len })
+        );
+    }
+}
+
+#[test]
+fn versioned_hash_mismatch_errors() {
+    let mut input = valid_input();
+    input[5] ^= 1;
+    assert_eq!(precompile::point_evaluation(&input), Err(PrecompileError::VersionedHashMismatch));
+}
+
+#[test]
+fn call_dispatches_to_the_precompile_and_charges_its_gas() {
+    let evm = call_point_evaluation(valid_input(), 192, 0xffff);
+    assert_eq!(evm.stack(), &[U256::one()]);
+    assert_eq!(&evm.memory()[192..256], &success_output()[..]);
+    // 7 次 PUSH、CALL 的 warm 费用 100 + 首次访问 0x0A 的 cold 附加费 2500、
+    // 返回区域把内存从 6 个字扩展到 8 个字，以及预编译本身的 50000
+    assert_eq!(evm.gas_used(), 7 * 3 + 100 + 2500 + 6 + POINT_EVALUATION_GAS);
+    assert_eq!(precompile::gas_cost(&POINT_EVALUATION_ADDRESS), Some(POINT_EVALUATION_GAS));
+}
+
+#[test]
+fn call_with_empty_input_fails_and_consumes_forwarded_gas() {
+    let evm = call_point_evaluation(Vec::new(), 0, 0xffff);
+    assert_eq!(evm.stack(), &[U256::zero()]);
+    assert!(evm.gas_used() > 0xffff);
+}
+
+#[test]
+fn call_with_too_little_gas_fails() {
+    let evm = call_point_evaluation(valid_input(), 192, POINT_EVALUATION_GAS - 1);
+    assert_eq!(evm.stack(), &[U256::zero()]);
+}
@@ -0,0 +1,19 @@
+// 指令耗时分析，只在开启 profiler 特性时编译
+#![cfg(feature = "profiler")]
+
+use evm::asm::assemble;
+use evm::{OpcodeProfiler, EVM};
+
+#[test]
+fn profiler_records_time_for_sha3() {
+    // 对 4KiB 内存求哈希
+    let mut evm = EVM::new(assemble("PUSH2 0x1000\nPUSH1 0x00\nSHA3\nPOP").unwrap());
+    let mut profiler = OpcodeProfiler::new();
+    evm.run_with_tracer(&mut profiler).unwrap();
+
+    let timings = profiler.timings();
+    assert_eq!(timings.len(), 4, "PUSH2、PUSH1、SHA3、POP 各一项");
+    assert!(!timings[&0x20].is_zero(), "SHA3 的耗时应大于 0");
+    assert_eq!(profiler.hottest(1).len(), 1);
+    assert_eq!(profiler.report(2).lines().count(), 2);
+}
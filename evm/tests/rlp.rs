@@ -0,0 +1,43 @@
+// RLP 编码的已知向量
+
+use ethereum_types::Address;
+use evm::rlp::{encode_bytes, encode_list, encode_u256};
+use primitive_types::U256;
+
+#[test]
+fn encodes_strings() {
+    assert_eq!(encode_bytes(b"dog"), vec![0x83, b'd', b'o', b'g']);
+    assert_eq!(encode_bytes(b""), vec![0x80]);
+    assert_eq!(encode_bytes(&[0x7f]), vec![0x7f]);
+    assert_eq!(encode_bytes(&[0x80]), vec![0x81, 0x80]);
+    let long = [b'a'; 56];
+    assert_eq!(encode_bytes(&long)[..2], [0xb8, 56]);
+}
+
+#[test]
+fn encodes_lists() {
+    assert_eq!(encode_list(&[]), vec![0xc0]);
+    assert_eq!(
+        encode_list(&[encode_bytes(b"cat"), encode_bytes(b"dog")]),
+        vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']
+    );
+}
+
+#[test]
+fn encodes_integers_minimally() {
+    assert_eq!(encode_u256(U256::zero()), vec![0x80]);
+    assert_eq!(encode_u256(U256::from(15)), vec![0x0f]);
+    assert_eq!(encode_u256(U256::from(1024)), vec![0x82, 0x04, 0x00]);
+}
+
+#[test]
+fn encodes_the_create_preimage() {
+    let sender: Address = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0".parse().unwrap();
+    let mut expected = vec![0xd6, 0x94];
+    expected.extend_from_slice(sender.as_bytes());
+    expected.push(0x80);
+    assert_eq!(encode_list(&[encode_bytes(sender.as_bytes()), encode_u256(U256::zero())]), expected);
+
+    *expected.last_mut().unwrap() = 0x01;
+    assert_eq!(encode_list(&[encode_bytes(sender.as_bytes()), encode_u256(U256::one())]), expected);
+}
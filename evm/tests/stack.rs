@@ -0,0 +1,39 @@
+// 栈操作：DUP/SWAP 的边界
+
+use evm::asm::assemble;
+use evm::{EvmError, EVM};
+use primitive_types::U256;
+
+// 依次压入 1..=count，再执行 op
+fn run_after_pushes(count: u64, op: &str) -> (EVM, Result<(), EvmError>) {
+    let pushes: String = (1..=count).map(|i| format!("PUSH1 0x{:02x}\n", i)).collect();
+    let mut evm = EVM::new(assemble(&format!("{}{}", pushes, op)).unwrap());
+    let result = evm.run();
+    (evm, result)
+}
+
+#[test]
+fn swap16_needs_seventeen_items() {
+    let (_, result) = run_after_pushes(16, "SWAP16");
+    assert_eq!(result, Err(EvmError::StackUnderflow { required: 17, actual: 16 }));
+
+    let (evm, result) = run_after_pushes(17, "SWAP16");
+    result.unwrap();
+    let stack = evm.stack();
+    assert_eq!((stack[0], stack[16]), (U256::from(17), U256::one()));
+}
+
+#[test]
+fn swap1_and_dup16_on_the_boundary() {
+    let (_, result) = run_after_pushes(1, "SWAP1");
+    assert_eq!(result, Err(EvmError::StackUnderflow { required: 2, actual: 1 }));
+    let (evm, result) = run_after_pushes(2, "SWAP1");
+    result.unwrap();
+    assert_eq!(evm.stack(), &[U256::from(2), U256::one()]);
+
+    let (_, result) = run_after_pushes(15, "DUP16");
+    assert_eq!(result, Err(EvmError::StackUnderflow { required: 16, actual: 15 }));
+    let (evm, result) = run_after_pushes(16, "DUP16");
+    result.unwrap();
+    assert_eq!(evm.stack().last(), Some(&U256::one()));
+}
@@ -0,0 +1,72 @@
+// 静态上下文（STATICCALL）中禁止修改状态的指令
+
+mod common;
+
+use common::run_with_callees;
+use ethereum_types::Address;
+use evm::asm::assemble;
+use evm::{EvmError, EVM};
+use primitive_types::U256;
+
+fn evm(source: &str, is_static: bool) -> EVM {
+    EVM::new(assemble(source).unwrap()).with_static(is_static).with_gas_limit(1_000_000)
+}
+
+fn run_static(source: &str) -> Result<(), EvmError> {
+    evm(source, true).run()
+}
+
+// 以 value 调用 0xcc（不传参数、不取返回数据）
+fn call_with_value(value: u8) -> String {
+    format!("PUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x{:02x}\nPUSH1 0xcc\nPUSH2 0xffff\nCALL", value)
+}
+
+// SSTORE、LOG、CREATE 和带 value 的 CALL 各一段
+fn state_changing_programs() -> [String; 5] {
+    [
+        "PUSH1 0x01\nPUSH1 0x00\nSSTORE".to_string(),
+        "PUSH1 0x00\nPUSH1 0x00\nLOG0".to_string(),
+        "PUSH1 0x01\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nLOG1".to_string(),
+        "PUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nCREATE".to_string(),
+        call_with_value(1),
+    ]
+}
+
+#[test]
+fn state_changing_opcodes_error_in_static_context() {
+    for source in &state_changing_programs() {
+        assert_eq!(run_static(source), Err(EvmError::StaticStateChange), "{}", source);
+    }
+}
+
+#[test]
+fn state_changing_opcodes_succeed_outside_static_context() {
+    for source in &state_changing_programs() {
+        let mut evm = evm(source, false);
+        assert_eq!(evm.run(), Ok(()), "{}", source);
+        assert!(evm.execution_result(None).success, "{}", source);
+    }
+    // CREATE 和 CALL 本身也要成功：压入的是新合约地址和 1，而不是 0
+    let mut create = evm(&state_changing_programs()[3], false);
+    create.run().unwrap();
+    assert!(!create.stack()[0].is_zero());
+    let mut call = evm(&call_with_value(1), false);
+    call.run().unwrap();
+    assert_eq!(call.stack(), &[U256::one()]);
+}
+
+#[test]
+fn reads_and_value_free_calls_are_allowed_in_static_context() {
+    assert_eq!(run_static("PUSH1 0x00\nSLOAD"), Ok(()));
+    assert_eq!(run_static(&call_with_value(0)), Ok(()));
+}
+
+#[test]
+fn staticcall_into_sstore_fails_the_sub_call() {
+    let a = Address::from_low_u64_be(0xaa);
+    let c = Address::from_low_u64_be(0xcc);
+    let code_a = "PUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0x00\nPUSH1 0xcc\nPUSH2 0xffff\nSTATICCALL";
+    let evm = run_with_callees(a, code_a, &[(c, "PUSH1 0x01\nPUSH1 0x00\nSSTORE")]);
+    assert_eq!(evm.stack(), &[U256::zero()]);
+    assert_eq!(evm.storage_of(c, U256::zero()), U256::zero());
+}
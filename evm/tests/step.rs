@@ -0,0 +1,92 @@
+// 单步执行、单步回退与按指令数分段执行
+
+use evm::asm::assemble;
+use evm::{RunState, EVM};
+use primitive_types::U256;
+
+// 0: PUSH1 0x2a  2: PUSH1 0x00  4: MSTORE  5: PUSH1 0x07  7: PUSH1 0x00  9: SSTORE
+const PROGRAM: &str = "PUSH1 0x2a\nPUSH1 0x00\nMSTORE\nPUSH1 0x07\nPUSH1 0x00\nSSTORE";
+
+fn evm() -> EVM {
+    EVM::new(assemble(PROGRAM).unwrap())
+}
+
+#[test]
+fn step_twice_then_back_restores_the_first_step() {
+    let mut evm = evm();
+    evm.step().unwrap();
+    let after_first = (evm.pc(), evm.stack().to_vec(), evm.gas_remaining());
+    evm.step().unwrap();
+    assert_eq!(evm.stack().len(), 2);
+
+    assert!(evm.step_back());
+    assert_eq!((evm.pc(), evm.stack().to_vec(), evm.gas_remaining()), after_first);
+    assert_eq!(evm.undo_len(), 1);
+}
+
+#[test]
+fn step_back_undoes_memory_and_storage_writes() {
+    let mut evm = evm();
+    evm.run_n(6).unwrap();
+    assert_eq!(evm.memory().len(), 32);
+    assert_eq!(evm.storage_entries_sorted(), vec![(U256::zero(), U256::from(7))]);
+
+    assert!(evm.step_back());
+    assert!(evm.storage_entries_sorted().is_empty());
+    for _ in 0..3 {
+        assert!(evm.step_back());
+    }
+    assert!(evm.memory().is_empty(), "回到 MSTORE 之前");
+    assert_eq!(evm.pc(), 4);
+}
+
+#[test]
+fn step_back_without_history_does_nothing() {
+    let mut evm = evm();
+    assert!(!evm.step_back());
+    let mut evm = self::evm().with_undo_depth(1);
+    evm.step().unwrap();
+    evm.step().unwrap();
+    assert!(evm.step_back());
+    assert!(!evm.step_back(), "只保留最近 1 步");
+    assert_eq!(evm.pc(), 2);
+}
+
+#[test]
+fn run_n_pauses_at_the_third_instruction() {
+    let mut evm = evm();
+    assert_eq!(evm.run_n(2).unwrap(), RunState::Paused);
+    assert_eq!(evm.pc(), 4);
+    assert_eq!(evm.stack(), &[U256::from(0x2a), U256::zero()]);
+
+    assert_eq!(evm.run_n(100).unwrap(), RunState::Finished);
+    assert!(evm.execution_result(None).success);
+}
+
+// 0: PUSH1 0x01  2: ADD（堆栈下溢）  3: PUSH1 0x02
+const FAILS_AT_ADD: &str = "PUSH1 0x01\nADD\nPUSH1 0x02";
+
+#[test]
+fn step_after_a_failed_instruction_does_nothing() {
+    let mut evm = EVM::new(assemble(FAILS_AT_ADD).unwrap());
+    evm.step().unwrap();
+    assert!(evm.step().is_err());
+    assert!(evm.is_halted());
+    assert!(!evm.execution_result(None).success);
+
+    let (pc, gas) = (evm.pc(), evm.gas_remaining());
+    evm.step().unwrap();
+    evm.run().unwrap();
+    assert_eq!((evm.pc(), evm.gas_remaining()), (pc, gas));
+    assert_eq!(evm.stack(), &[U256::one()], "出错指令之后的 PUSH1 不应执行");
+}
+
+#[test]
+fn run_n_after_a_failed_step_does_no_more_work() {
+    let mut evm = EVM::new(assemble(FAILS_AT_ADD).unwrap());
+    assert!(evm.run_n(5).is_err());
+    let (pc, gas) = (evm.pc(), evm.gas_remaining());
+    assert_eq!(evm.run_n(5).unwrap(), RunState::Finished);
+    assert_eq!((evm.pc(), evm.gas_remaining()), (pc, gas));
+    assert_eq!(evm.stack(), &[U256::one()]);
+}
@@ -0,0 +1,76 @@
+// 执行追踪：HistoryTracer 与内存读写回调
+
+use evm::asm::assemble;
+use evm::{HistoryTracer, Tracer, EVM};
+use primitive_types::U256;
+
+#[test]
+fn history_tracer_records_every_step() {
+    // 0: PUSH1 1  2: PUSH1 2  4: ADD  5: PUSH1 0  7: MSTORE
+    let mut evm = EVM::new(assemble("PUSH1 0x01\nPUSH1 0x02\nADD\nPUSH1 0x00\nMSTORE").unwrap());
+    let mut tracer = HistoryTracer::new();
+    evm.run_with_tracer(&mut tracer).unwrap();
+
+    let history = tracer.history();
+    assert_eq!(history.iter().map(|step| step.pc).collect::<Vec<_>>(), vec![0, 2, 4, 5, 7]);
+    assert_eq!(history.iter().map(|step| step.opcode).collect::<Vec<_>>(), vec![0x60, 0x60, 0x01, 0x60, 0x52]);
+    assert_eq!(history[2].stack, vec![U256::one(), U256::from(2)]);
+    assert_eq!(history[4].stack, vec![U256::from(3), U256::zero()]);
+    assert!(history.iter().all(|step| step.memory_len == 0), "快照在指令执行前记录");
+    assert_eq!(history[0].gas_remaining - history[1].gas_remaining, 3);
+}
+
+#[test]
+fn history_tracer_keeps_only_the_configured_stack_depth() {
+    let mut evm = EVM::new(assemble("PUSH1 0x01\nPUSH1 0x02\nPUSH1 0x03\nSTOP").unwrap());
+    let mut tracer = HistoryTracer::new().with_stack_depth(2);
+    evm.run_with_tracer(&mut tracer).unwrap();
+    assert_eq!(tracer.history().last().unwrap().stack, vec![U256::from(2), U256::from(3)]);
+}
+
+// 记录内存读写的偏移与长度
+#[derive(Default)]
+struct MemoryRecorder {
+    writes: Vec<(usize, usize)>,
+    reads: Vec<(usize, usize)>,
+}
+
+impl Tracer for MemoryRecorder {
+    fn on_memory_write(&mut self, offset: usize, bytes: &[u8]) {
+        self.writes.push((offset, bytes.len()));
+    }
+
+    fn on_memory_read(&mut self, offset: usize, len: usize) {
+        self.reads.push((offset, len));
+    }
+}
+
+#[test]
+fn memory_hooks_see_mstore_then_mload() {
+    let mut evm = EVM::new(assemble("PUSH1 0x2a\nPUSH1 0x20\nMSTORE\nPUSH1 0x20\nMLOAD").unwrap());
+    let mut recorder = MemoryRecorder::default();
+    evm.run_with_tracer(&mut recorder).unwrap();
+    assert_eq!(recorder.writes, vec![(0x20, 32)]);
+    assert_eq!(recorder.reads, vec![(0x20, 32)]);
+    assert_eq!(evm.stack(), &[U256::from(0x2a)]);
+}
+
+// 记录每条指令执行后剩余 gas 的百分比，与 trace 输出中的数值相同
+#[derive(Default)]
+struct GasPercentRecorder(Vec<f64>);
+
+impl Tracer for GasPercentRecorder {
+    fn after_op(&mut self, evm: &EVM, _op: u8) {
+        self.0.push(evm.gas_remaining_percent());
+    }
+}
+
+#[test]
+fn remaining_gas_percent_decreases_over_steps() {
+    let mut evm = EVM::new(assemble("PUSH1 0x01\nPUSH1 0x02\nADD\nPUSH1 0x00\nMSTORE").unwrap()).with_gas_limit(100);
+    assert_eq!(evm.gas_remaining_percent(), 100.0);
+    let mut recorder = GasPercentRecorder::default();
+    evm.run_with_tracer(&mut recorder).unwrap();
+    // PUSH1 3，ADD 3，MSTORE 3 + 内存扩展 3
+    assert_eq!(recorder.0, vec![97.0, 94.0, 91.0, 88.0, 82.0]);
+}
@@ -0,0 +1,37 @@
+// 遇到未知 opcode 时的三种处理策略
+
+use evm::{EvmError, UnknownOpcodePolicy, EVM};
+use primitive_types::U256;
+
+// PUSH1 1, 0x0c（未分配的 opcode）, PUSH1 2
+const CODE: [u8; 5] = [0x60, 0x01, 0x0c, 0x60, 0x02];
+
+fn run(policy: UnknownOpcodePolicy) -> (EVM, Result<(), EvmError>) {
+    let mut evm = EVM::new(CODE.to_vec()).with_gas_limit(1000).with_unknown_opcode_policy(policy);
+    let result = evm.run();
+    (evm, result)
+}
+
+#[test]
+fn halt_stops_and_consumes_all_gas() {
+    let (evm, result) = run(UnknownOpcodePolicy::default());
+    result.unwrap();
+    assert!(!evm.execution_result(None).success);
+    assert_eq!(evm.stack(), &[U256::one()], "之后的指令不再执行");
+    assert_eq!(evm.gas_remaining(), 0);
+}
+
+#[test]
+fn error_returns_invalid_opcode() {
+    let (_, result) = run(UnknownOpcodePolicy::Error);
+    assert_eq!(result, Err(EvmError::InvalidOpcode(0x0c)));
+}
+
+#[test]
+fn skip_continues_with_the_next_byte() {
+    let (evm, result) = run(UnknownOpcodePolicy::Skip);
+    result.unwrap();
+    assert!(evm.execution_result(None).success);
+    assert_eq!(evm.stack(), &[U256::one(), U256::from(2)]);
+    assert_eq!(evm.gas_used(), 6);
+}